@@ -1,17 +1,18 @@
 use crate::msg::AskAsset;
-use crate::msg::{DexAction, OfferAsset, SwapRouter};
-use crate::state::SWAP_FEE;
+use crate::msg::{AssetLists, DexAction, FeeMode, OfferAsset, Recipient, SwapRouter};
+use crate::state::{ASSET_LISTS, SWAP_FEE};
 use abstract_core::objects::AnsEntryConvertor;
-use abstract_core::objects::{DexAssetPairing, PoolReference};
+use abstract_core::objects::{ContractEntry, DexAssetPairing, LpToken, PoolReference, PoolType};
 use abstract_dex_adapter_traits::DexError;
 use abstract_sdk::core::objects::AnsAsset;
 use abstract_sdk::core::objects::AssetEntry;
 use abstract_sdk::cw_helpers::Chargeable;
-use abstract_sdk::features::AbstractNameService;
+use abstract_sdk::features::{AbstractNameService, AbstractRegistryAccess};
+use abstract_sdk::AccountVerification;
 use abstract_sdk::Execution;
-use cosmwasm_std::{CosmosMsg, Decimal, Deps, StdError};
+use cosmwasm_std::{Addr, CosmosMsg, Decimal, Deps, StdError};
 
-use cw_asset::Asset;
+use cw_asset::{Asset, AssetInfo};
 
 use abstract_dex_adapter_traits::DexCommand;
 
@@ -20,37 +21,84 @@ pub const PROVIDE_LIQUIDITY_SYM: u64 = 7543;
 pub const WITHDRAW_LIQUIDITY: u64 = 7546;
 pub const SWAP: u64 = 7544;
 pub const CUSTOM_SWAP: u64 = 7545;
+pub const CREATE_POOL: u64 = 7541;
 
-impl<T> DexAdapter for T where T: AbstractNameService + Execution {}
+/// Name of the factory contracts of the dexes in the ANS
+pub const FACTORY: &str = "factory";
+/// Name of the dex adapters of remote chains in the ANS, registered per host chain
+pub const REMOTE_DEX_ADAPTER: &str = "dex_adapter";
+
+impl<T> DexAdapter for T where T: AbstractNameService + AbstractRegistryAccess + Execution {}
 
 pub(crate) type ReplyId = u64;
 
-pub trait DexAdapter: AbstractNameService + Execution {
+/// Messages of a dex action resolved on a local dex
+pub struct ResolvedAction {
+    pub msgs: Vec<CosmosMsg>,
+    pub reply_id: ReplyId,
+    /// Outputs that have to be transferred to the recipient of the action after it's executed
+    pub forward: Option<Forward>,
+    /// Output of the action that the adapter fee is charged on after it's executed
+    pub fee_asset: Option<AssetInfo>,
+}
+
+/// Outputs of an action for a recipient that the dex can't send them to
+#[cosmwasm_schema::cw_serde]
+pub struct Forward {
+    pub recipient: Addr,
+    pub assets: Vec<AssetInfo>,
+}
+
+impl ResolvedAction {
+    fn new(msgs: Vec<CosmosMsg>, reply_id: ReplyId) -> Self {
+        Self {
+            msgs,
+            reply_id,
+            forward: None,
+            fee_asset: None,
+        }
+    }
+
+    fn with_forward(self, forward: Option<Forward>) -> Self {
+        Self { forward, ..self }
+    }
+
+    fn with_fee_asset(self, fee_asset: Option<AssetInfo>) -> Self {
+        Self { fee_asset, ..self }
+    }
+}
+
+pub trait DexAdapter: AbstractNameService + AbstractRegistryAccess + Execution {
     /// resolve the provided dex action on a local dex
     fn resolve_dex_action(
         &self,
         deps: Deps,
         action: DexAction,
         exchange: &dyn DexCommand,
-    ) -> Result<(Vec<CosmosMsg>, ReplyId), DexError> {
+    ) -> Result<ResolvedAction, DexError> {
         Ok(match action {
-            DexAction::ProvideLiquidity { assets, max_spread } => {
+            DexAction::ProvideLiquidity {
+                assets,
+                max_spread,
+                recipient,
+            } => {
                 if assets.len() < 2 {
                     return Err(DexError::TooFewAssets {});
                 }
-                (
-                    self.resolve_provide_liquidity(deps, assets, exchange, max_spread)?,
-                    PROVIDE_LIQUIDITY,
-                )
+                let recipient = self.resolve_recipient(deps, recipient)?;
+                let (msgs, forward) =
+                    self.resolve_provide_liquidity(deps, assets, exchange, max_spread, recipient)?;
+                ResolvedAction::new(msgs, PROVIDE_LIQUIDITY).with_forward(forward)
             }
             DexAction::ProvideLiquiditySymmetric {
                 offer_asset,
                 paired_assets,
+                ..
             } => {
                 if paired_assets.is_empty() {
                     return Err(DexError::TooFewAssets {});
                 }
-                (
+                ResolvedAction::new(
                     self.resolve_provide_liquidity_symmetric(
                         deps,
                         offer_asset,
@@ -60,16 +108,46 @@ pub trait DexAdapter: AbstractNameService + Execution {
                     PROVIDE_LIQUIDITY_SYM,
                 )
             }
-            DexAction::WithdrawLiquidity { lp_token, amount } => (
-                self.resolve_withdraw_liquidity(deps, AnsAsset::new(lp_token, amount), exchange)?,
-                WITHDRAW_LIQUIDITY,
-            ),
+            DexAction::WithdrawLiquidity {
+                lp_token,
+                amount,
+                recipient,
+            } => {
+                let recipient = self.resolve_recipient(deps, recipient)?;
+                let (msgs, forward) = self.resolve_withdraw_liquidity(
+                    deps,
+                    AnsAsset::new(lp_token, amount),
+                    None,
+                    exchange,
+                    recipient,
+                )?;
+                ResolvedAction::new(msgs, WITHDRAW_LIQUIDITY).with_forward(forward)
+            }
+            DexAction::WithdrawLiquiditySingleAsset {
+                lp_token,
+                amount,
+                ask_asset,
+                recipient,
+            } => {
+                let recipient = self.resolve_recipient(deps, recipient)?;
+                let (msgs, forward) = self.resolve_withdraw_liquidity(
+                    deps,
+                    AnsAsset::new(lp_token, amount),
+                    Some(ask_asset),
+                    exchange,
+                    recipient,
+                )?;
+                ResolvedAction::new(msgs, WITHDRAW_LIQUIDITY).with_forward(forward)
+            }
             DexAction::Swap {
                 offer_asset,
                 ask_asset,
                 max_spread,
                 belief_price,
-            } => (
+                recipient,
+                fee_mode,
+            } => {
+                let recipient = self.resolve_recipient(deps, recipient)?;
                 self.resolve_swap(
                     deps,
                     offer_asset,
@@ -77,15 +155,16 @@ pub trait DexAdapter: AbstractNameService + Execution {
                     exchange,
                     max_spread,
                     belief_price,
-                )?,
-                SWAP,
-            ),
+                    recipient,
+                    fee_mode,
+                )?
+            }
             DexAction::CustomSwap {
                 offer_assets,
                 ask_assets,
                 max_spread,
                 router,
-            } => (
+            } => ResolvedAction::new(
                 self.resolve_custom_swap(
                     deps,
                     offer_assets,
@@ -96,9 +175,241 @@ pub trait DexAdapter: AbstractNameService + Execution {
                 )?,
                 CUSTOM_SWAP,
             ),
+            DexAction::RawSwap {
+                pool,
+                offer_asset,
+                ask_asset,
+                max_spread,
+                belief_price,
+            } => {
+                let pool = pool.check(deps.api)?;
+                let mut offer_asset = offer_asset.check(deps.api, None)?;
+                let ask_asset = ask_asset.check(deps.api, None)?;
+                // account for fee
+                let fee = SWAP_FEE.load(deps.storage)?;
+                let fee_msg = offer_asset.charge_usage_fee(fee)?;
+                let mut swap_msgs =
+                    exchange.swap(deps, pool, offer_asset, ask_asset, belief_price, max_spread)?;
+                swap_msgs.extend(fee_msg);
+                ResolvedAction::new(swap_msgs, SWAP)
+            }
+            DexAction::RawProvideLiquidity {
+                pool,
+                assets,
+                max_spread,
+            } => {
+                if assets.len() < 2 {
+                    return Err(DexError::TooFewAssets {});
+                }
+                let assets = assets
+                    .iter()
+                    .map(|asset| asset.check(deps.api, None))
+                    .collect::<Result<Vec<Asset>, _>>()?;
+                ResolvedAction::new(
+                    exchange.provide_liquidity(deps, pool.check(deps.api)?, assets, max_spread)?,
+                    PROVIDE_LIQUIDITY,
+                )
+            }
+            DexAction::RawWithdrawLiquidity { pool, lp_token } => ResolvedAction::new(
+                exchange.withdraw_liquidity(
+                    deps,
+                    pool.check(deps.api)?,
+                    lp_token.check(deps.api, None)?,
+                )?,
+                WITHDRAW_LIQUIDITY,
+            ),
+            DexAction::CreatePool {
+                assets,
+                pool_type,
+                initial_liquidity,
+            } => {
+                if assets.len() < 2 {
+                    return Err(DexError::TooFewAssets {});
+                }
+                ResolvedAction::new(
+                    self.resolve_create_pool(
+                        deps,
+                        assets,
+                        pool_type,
+                        initial_liquidity.unwrap_or_default(),
+                        exchange,
+                    )?,
+                    CREATE_POOL,
+                )
+            }
         })
     }
 
+    fn resolve_create_pool(
+        &self,
+        deps: Deps,
+        mut assets: Vec<AssetEntry>,
+        pool_type: PoolType,
+        mut initial_liquidity: Vec<OfferAsset>,
+        exchange: &dyn DexCommand,
+    ) -> Result<Vec<CosmosMsg>, DexError> {
+        for asset in assets.iter_mut() {
+            asset.format();
+        }
+        for deposit in initial_liquidity.iter_mut() {
+            deposit.name.format();
+        }
+        if let Some(deposit) = initial_liquidity
+            .iter()
+            .find(|deposit| !assets.contains(&deposit.name))
+        {
+            return Err(DexError::ArgumentMismatch(
+                deposit.name.to_string(),
+                assets.iter().map(ToString::to_string).collect(),
+            ));
+        }
+
+        let ans = self.name_service(deps);
+        let pool_assets = assets
+            .into_iter()
+            .map(|asset| {
+                let amount = initial_liquidity
+                    .iter()
+                    .find(|deposit| deposit.name == asset)
+                    .map(|deposit| deposit.amount)
+                    .unwrap_or_default();
+                ans.query(&AnsAsset::new(asset, amount))
+            })
+            .collect::<Result<Vec<Asset>, _>>()?;
+        let factory = ans.query(&ContractEntry {
+            protocol: exchange.name().to_string(),
+            contract: FACTORY.to_string(),
+        })?;
+        exchange.create_pool(deps, factory, pool_assets, pool_type)
+    }
+
+    /// Reject actions on assets that aren't allowed by the asset lists of the account
+    fn assert_assets_allowed(
+        &self,
+        deps: Deps,
+        proxy: &Addr,
+        action: &DexAction,
+        dex: &str,
+    ) -> Result<(), DexError> {
+        let Some(lists) = ASSET_LISTS.may_load(deps.storage, proxy)? else {
+            return Ok(());
+        };
+        let lp_token = |assets: Vec<AssetEntry>| -> AssetEntry { LpToken::new(dex, assets).into() };
+
+        let entries = match action {
+            DexAction::ProvideLiquidity { assets, .. } => {
+                let mut entries: Vec<AssetEntry> = assets.iter().map(|a| a.name.clone()).collect();
+                entries.push(lp_token(entries.clone()));
+                entries
+            }
+            DexAction::ProvideLiquiditySymmetric {
+                offer_asset,
+                paired_assets,
+                ..
+            } => {
+                let mut entries = vec![offer_asset.name.clone()];
+                entries.extend(paired_assets.iter().cloned());
+                entries.push(lp_token(entries.clone()));
+                entries
+            }
+            DexAction::WithdrawLiquidity { lp_token, .. }
+            | DexAction::WithdrawLiquiditySingleAsset { lp_token, .. } => {
+                let mut entries = AnsEntryConvertor::new(lp_token.clone()).lp_token()?.assets;
+                entries.push(lp_token.clone());
+                entries
+            }
+            DexAction::Swap {
+                offer_asset,
+                ask_asset,
+                ..
+            } => vec![offer_asset.name.clone(), ask_asset.clone()],
+            DexAction::CustomSwap {
+                offer_assets,
+                ask_assets,
+                ..
+            } => offer_assets
+                .iter()
+                .chain(ask_assets)
+                .map(|a| a.name.clone())
+                .collect(),
+            DexAction::CreatePool { assets, .. } => {
+                let mut entries = assets.clone();
+                entries.push(lp_token(assets.clone()));
+                entries
+            }
+            DexAction::RawSwap {
+                offer_asset,
+                ask_asset,
+                ..
+            } => {
+                let infos = vec![
+                    offer_asset.check(deps.api, None)?.info,
+                    ask_asset.check(deps.api, None)?,
+                ];
+                return self.assert_raw_assets_allowed(deps, &lists, infos);
+            }
+            DexAction::RawProvideLiquidity { assets, .. } => {
+                let infos = assets
+                    .iter()
+                    .map(|asset| Ok(asset.check(deps.api, None)?.info))
+                    .collect::<Result<_, DexError>>()?;
+                return self.assert_raw_assets_allowed(deps, &lists, infos);
+            }
+            DexAction::RawWithdrawLiquidity { lp_token, .. } => {
+                let infos = vec![lp_token.check(deps.api, None)?.info];
+                return self.assert_raw_assets_allowed(deps, &lists, infos);
+            }
+        };
+
+        for mut entry in entries {
+            entry.format();
+            if !lists.allows(&entry) {
+                return Err(DexError::AssetNotAllowed(entry.to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Compare the assets of a raw action with the assets the entries of the lists resolve to
+    fn assert_raw_assets_allowed(
+        &self,
+        deps: Deps,
+        lists: &AssetLists,
+        infos: Vec<AssetInfo>,
+    ) -> Result<(), DexError> {
+        let ans = self.name_service(deps);
+        let allowed = lists
+            .allowed
+            .as_ref()
+            .map(|allowed| ans.query(allowed))
+            .transpose()?;
+        let denied = ans.query(&lists.denied)?;
+        let is_allowed = |info: &AssetInfo| {
+            !denied.contains(info) && allowed.as_ref().map_or(true, |a| a.contains(info))
+        };
+        match infos.into_iter().find(|info| !is_allowed(info)) {
+            Some(info) => Err(DexError::AssetNotAllowed(info.to_string())),
+            None => Ok(()),
+        }
+    }
+
+    /// Resolve the address that receives the output of an action
+    fn resolve_recipient(
+        &self,
+        deps: Deps,
+        recipient: Option<Recipient>,
+    ) -> Result<Option<Addr>, DexError> {
+        recipient
+            .map(|recipient| match recipient {
+                Recipient::Address(address) => deps.api.addr_validate(&address).map_err(Into::into),
+                Recipient::Account(account_id) => self
+                    .account_registry(deps)
+                    .proxy_address(account_id)
+                    .map_err(Into::into),
+            })
+            .transpose()
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn resolve_swap(
         &self,
@@ -108,7 +419,9 @@ pub trait DexAdapter: AbstractNameService + Execution {
         exchange: &dyn DexCommand,
         max_spread: Option<Decimal>,
         belief_price: Option<Decimal>,
-    ) -> Result<Vec<CosmosMsg>, DexError> {
+        recipient: Option<Addr>,
+        fee_mode: FeeMode,
+    ) -> Result<ResolvedAction, DexError> {
         let AnsAsset {
             name: mut offer_asset,
             amount: offer_amount,
@@ -124,22 +437,54 @@ pub trait DexAdapter: AbstractNameService + Execution {
             exchange.pair_address(deps, ans.host(), (offer_asset.clone(), ask_asset))?;
         let mut offer_asset: Asset = Asset::new(offer_asset_info, offer_amount);
         // account for fee
-        let fee = SWAP_FEE.load(deps.storage)?;
-        let fee_msg = offer_asset.charge_usage_fee(fee)?;
-        let mut swap_msgs = exchange.swap(
-            deps,
-            pair_address,
-            offer_asset,
-            ask_asset_info,
-            belief_price,
-            max_spread,
-        )?;
+        let (fee_msg, fee_asset) = match fee_mode {
+            FeeMode::OfferAsset => {
+                let fee = SWAP_FEE.load(deps.storage)?;
+                (offer_asset.charge_usage_fee(fee)?, None)
+            }
+            // charged on the output of the swap, measured in the reply
+            FeeMode::AskAsset => (None, Some(ask_asset_info.clone())),
+        };
+        // the fee can't be charged when the dex sends the output to the recipient
+        let swap_to = recipient
+            .clone()
+            .filter(|_| fee_asset.is_none())
+            .map(|recipient| {
+                exchange.swap_to(
+                    deps,
+                    pair_address.clone(),
+                    offer_asset.clone(),
+                    ask_asset_info.clone(),
+                    belief_price,
+                    max_spread,
+                    recipient,
+                )
+            });
+        let (mut swap_msgs, forward) = match swap_to {
+            Some(Err(DexError::NotImplemented(_))) | None => (
+                exchange.swap(
+                    deps,
+                    pair_address,
+                    offer_asset,
+                    ask_asset_info.clone(),
+                    belief_price,
+                    max_spread,
+                )?,
+                recipient.map(|recipient| Forward {
+                    recipient,
+                    assets: vec![ask_asset_info],
+                }),
+            ),
+            Some(swap_to) => (swap_to?, None),
+        };
         // insert fee msg
         if let Some(f) = fee_msg {
             swap_msgs.push(f)
         }
 
-        Ok(swap_msgs)
+        Ok(ResolvedAction::new(swap_msgs, SWAP)
+            .with_forward(forward)
+            .with_fee_asset(fee_asset))
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -182,7 +527,8 @@ pub trait DexAdapter: AbstractNameService + Execution {
         offer_assets: Vec<OfferAsset>,
         exchange: &dyn DexCommand,
         max_spread: Option<Decimal>,
-    ) -> Result<Vec<CosmosMsg>, DexError> {
+        recipient: Option<Addr>,
+    ) -> Result<(Vec<CosmosMsg>, Option<Forward>), DexError> {
         let ans = self.name_service(deps);
         let assets = ans.query(&offer_assets)?;
 
@@ -195,9 +541,34 @@ pub trait DexAdapter: AbstractNameService + Execution {
         let pair_address = exchange.pair_address(
             deps,
             ans.host(),
-            (pair_assets.swap_remove(0), pair_assets.swap_remove(0)),
+            (pair_assets[0].clone(), pair_assets[1].clone()),
         )?;
-        exchange.provide_liquidity(deps, pair_address, assets, max_spread)
+        let Some(recipient) = recipient else {
+            return Ok((
+                exchange.provide_liquidity(deps, pair_address, assets, max_spread)?,
+                None,
+            ));
+        };
+        match exchange.provide_liquidity_to(
+            deps,
+            pair_address.clone(),
+            assets.clone(),
+            max_spread,
+            recipient.clone(),
+        ) {
+            Err(DexError::NotImplemented(_)) => {
+                let lp_token: AssetEntry = LpToken::new(exchange.name(), pair_assets).into();
+                let forward = Forward {
+                    recipient,
+                    assets: vec![ans.query(&lp_token)?],
+                };
+                Ok((
+                    exchange.provide_liquidity(deps, pair_address, assets, max_spread)?,
+                    Some(forward),
+                ))
+            }
+            msgs => Ok((msgs?, None)),
+        }
     }
 
     fn resolve_provide_liquidity_symmetric(
@@ -218,20 +589,43 @@ pub trait DexAdapter: AbstractNameService + Execution {
         exchange.provide_liquidity_symmetric(deps, pair_address, offer_asset, paired_asset_infos)
     }
 
-    /// @todo
+    /// Withdraw the liquidity into all the assets of the pool, or only into `ask_asset` when set
     fn resolve_withdraw_liquidity(
         &self,
         deps: Deps,
         lp_token: OfferAsset,
+        ask_asset: Option<AssetEntry>,
         exchange: &dyn DexCommand,
-    ) -> Result<Vec<CosmosMsg>, DexError> {
+        recipient: Option<Addr>,
+    ) -> Result<(Vec<CosmosMsg>, Option<Forward>), DexError> {
         let ans = self.name_service(deps);
 
         let lp_asset = ans.query(&lp_token)?;
 
-        let lp_pairing: DexAssetPairing =
-            AnsEntryConvertor::new(AnsEntryConvertor::new(lp_token.name).lp_token()?)
-                .dex_asset_pairing()?;
+        let lp_token = AnsEntryConvertor::new(lp_token.name).lp_token()?;
+        let ask_asset = ask_asset
+            .map(|mut ask_asset| -> Result<_, DexError> {
+                ask_asset.format();
+                if !lp_token.assets.contains(&ask_asset) {
+                    return Err(DexError::ArgumentMismatch(
+                        ask_asset.to_string(),
+                        lp_token.assets.iter().map(ToString::to_string).collect(),
+                    ));
+                }
+                Ok(ans.query(&ask_asset)?)
+            })
+            .transpose()?;
+        // the withdrawn assets are always sent to the sender
+        let forward = recipient
+            .map(|recipient| -> Result<_, DexError> {
+                let assets = match &ask_asset {
+                    Some(ask_asset) => vec![ask_asset.clone()],
+                    None => ans.query(&lp_token.assets)?,
+                };
+                Ok(Forward { recipient, assets })
+            })
+            .transpose()?;
+        let lp_pairing: DexAssetPairing = AnsEntryConvertor::new(lp_token).dex_asset_pairing()?;
 
         let mut pool_ids = ans.query(&lp_pairing)?;
         // TODO: when resolving if there are more than one, get the metadata and choose the one matching the assets
@@ -244,6 +638,12 @@ pub trait DexAdapter: AbstractNameService + Execution {
         }
 
         let PoolReference { pool_address, .. } = pool_ids.pop().unwrap();
-        exchange.withdraw_liquidity(deps, pool_address, lp_asset)
+        let msgs = match ask_asset {
+            Some(ask_asset) => {
+                exchange.withdraw_liquidity_single_asset(deps, pool_address, lp_asset, ask_asset)?
+            }
+            None => exchange.withdraw_liquidity(deps, pool_address, lp_asset)?,
+        };
+        Ok((msgs, forward))
     }
 }
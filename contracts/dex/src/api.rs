@@ -2,18 +2,22 @@
 // It cannot be in abstract-os because it does not have a dependency on sdk (as it shouldn't)
 use crate::{
     msg::{
-        AskAsset, DexAction, DexExecuteMsg, DexName, DexQueryMsg, OfferAsset, SimulateSwapResponse,
-        SwapRouter,
+        AskAsset, DcaSchedule, DcaSchedulesResponse, DexAction, DexExecuteMsg, DexName,
+        DexQueryMsg, DryRunResponse, FeeMode, LpValueResponse, OfferAsset, Order, OrdersResponse,
+        PositionsResponse, Recipient, SimulateSwapResponse, SwapRouter, TwapOrder,
+        TwapOrdersResponse,
     },
     EXCHANGE,
 };
-use abstract_core::objects::{module::ModuleId, AssetEntry};
+use abstract_core::objects::{module::ModuleId, AssetEntry, PoolType, UncheckedPoolAddress};
 use abstract_sdk::AdapterInterface;
 use abstract_sdk::{
     features::{AccountIdentification, Dependencies},
     AbstractSdkResult,
 };
 use cosmwasm_std::{CosmosMsg, Decimal, Deps, Uint128};
+use cw_asset::{AssetInfoUnchecked, AssetUnchecked};
+use cw_utils::Expiration;
 use serde::de::DeserializeOwned;
 
 // API for Abstract SDK users
@@ -26,6 +30,8 @@ pub trait DexInterface: AccountIdentification + Dependencies {
             deps,
             name,
             module_id: EXCHANGE,
+            deadline: None,
+            fee_mode: FeeMode::default(),
         }
     }
 }
@@ -38,6 +44,8 @@ pub struct Dex<'a, T: DexInterface> {
     name: DexName,
     module_id: ModuleId<'a>,
     deps: Deps<'a>,
+    deadline: Option<Expiration>,
+    fee_mode: FeeMode,
 }
 
 impl<'a, T: DexInterface> Dex<'a, T> {
@@ -46,6 +54,19 @@ impl<'a, T: DexInterface> Dex<'a, T> {
         Self { module_id, ..self }
     }
 
+    /// Reject the actions of the DEX when they are executed after the deadline
+    pub fn with_deadline(self, deadline: Expiration) -> Self {
+        Self {
+            deadline: Some(deadline),
+            ..self
+        }
+    }
+
+    /// Set the asset that the adapter fee of swaps is charged in
+    pub fn with_fee_mode(self, fee_mode: FeeMode) -> Self {
+        Self { fee_mode, ..self }
+    }
+
     /// returns DEX name
     fn dex_name(&self) -> DexName {
         self.name.clone()
@@ -65,6 +86,8 @@ impl<'a, T: DexInterface> Dex<'a, T> {
             DexExecuteMsg::Action {
                 dex: self.dex_name(),
                 action,
+                return_proceeds: false,
+                deadline: self.deadline,
             },
         )
     }
@@ -82,6 +105,27 @@ impl<'a, T: DexInterface> Dex<'a, T> {
             ask_asset,
             belief_price,
             max_spread,
+            recipient: None,
+            fee_mode: self.fee_mode.clone(),
+        })
+    }
+
+    /// Swap assets in the DEX and send the returned asset to the recipient
+    pub fn swap_to(
+        &self,
+        offer_asset: OfferAsset,
+        ask_asset: AssetEntry,
+        max_spread: Option<Decimal>,
+        belief_price: Option<Decimal>,
+        recipient: Recipient,
+    ) -> AbstractSdkResult<CosmosMsg> {
+        self.request(DexAction::Swap {
+            offer_asset,
+            ask_asset,
+            belief_price,
+            max_spread,
+            recipient: Some(recipient),
+            fee_mode: self.fee_mode.clone(),
         })
     }
 
@@ -107,7 +151,11 @@ impl<'a, T: DexInterface> Dex<'a, T> {
         assets: Vec<OfferAsset>,
         max_spread: Option<Decimal>,
     ) -> AbstractSdkResult<CosmosMsg> {
-        self.request(DexAction::ProvideLiquidity { assets, max_spread })
+        self.request(DexAction::ProvideLiquidity {
+            assets,
+            max_spread,
+            recipient: None,
+        })
     }
 
     /// Provide symmetrict liquidity in the DEX
@@ -119,6 +167,7 @@ impl<'a, T: DexInterface> Dex<'a, T> {
         self.request(DexAction::ProvideLiquiditySymmetric {
             offer_asset,
             paired_assets,
+            max_paired_assets: None,
         })
     }
 
@@ -128,7 +177,151 @@ impl<'a, T: DexInterface> Dex<'a, T> {
         lp_token: AssetEntry,
         amount: Uint128,
     ) -> AbstractSdkResult<CosmosMsg> {
-        self.request(DexAction::WithdrawLiquidity { lp_token, amount })
+        self.request(DexAction::WithdrawLiquidity {
+            lp_token,
+            amount,
+            recipient: None,
+        })
+    }
+
+    /// Withdraw liquidity from the DEX into a single asset of the pool
+    pub fn withdraw_liquidity_single_asset(
+        &self,
+        lp_token: AssetEntry,
+        amount: Uint128,
+        ask_asset: AssetEntry,
+    ) -> AbstractSdkResult<CosmosMsg> {
+        self.request(DexAction::WithdrawLiquiditySingleAsset {
+            lp_token,
+            amount,
+            ask_asset,
+            recipient: None,
+        })
+    }
+
+    /// Create a pool through the factory of the DEX
+    pub fn create_pool(
+        &self,
+        assets: Vec<AssetEntry>,
+        pool_type: PoolType,
+        initial_liquidity: Option<Vec<OfferAsset>>,
+    ) -> AbstractSdkResult<CosmosMsg> {
+        self.request(DexAction::CreatePool {
+            assets,
+            pool_type,
+            initial_liquidity,
+        })
+    }
+
+    /// Swap assets on a pool that is not registered in the ANS
+    pub fn raw_swap(
+        &self,
+        pool: UncheckedPoolAddress,
+        offer_asset: AssetUnchecked,
+        ask_asset: AssetInfoUnchecked,
+        max_spread: Option<Decimal>,
+        belief_price: Option<Decimal>,
+    ) -> AbstractSdkResult<CosmosMsg> {
+        self.request(DexAction::RawSwap {
+            pool,
+            offer_asset,
+            ask_asset,
+            max_spread,
+            belief_price,
+        })
+    }
+
+    /// Execute several actions in the DEX in order.
+    /// A zero amount in an action takes the amount received from the previous action.
+    pub fn batch(&self, actions: Vec<DexAction>) -> AbstractSdkResult<CosmosMsg> {
+        let adapters = self.base.adapters(self.deps);
+
+        adapters.request(
+            self.dex_module_id(),
+            DexExecuteMsg::Batch {
+                actions: actions
+                    .into_iter()
+                    .map(|action| (self.dex_name(), action))
+                    .collect(),
+            },
+        )
+    }
+
+    /// Place an order that a keeper executes on the DEX once its trigger is met
+    pub fn place_order(&self, order: Order) -> AbstractSdkResult<CosmosMsg> {
+        let adapters = self.base.adapters(self.deps);
+
+        adapters.request(
+            self.dex_module_id(),
+            DexExecuteMsg::PlaceOrder {
+                dex: self.dex_name(),
+                order,
+            },
+        )
+    }
+
+    /// Cancel an open order of the account
+    pub fn cancel_order(&self, order_id: u64) -> AbstractSdkResult<CosmosMsg> {
+        let adapters = self.base.adapters(self.deps);
+
+        adapters.request(
+            self.dex_module_id(),
+            DexExecuteMsg::CancelOrder { order_id },
+        )
+    }
+
+    /// Create a schedule of recurring swaps that keepers execute on the DEX once per interval
+    pub fn create_dca(&self, schedule: DcaSchedule) -> AbstractSdkResult<CosmosMsg> {
+        let adapters = self.base.adapters(self.deps);
+
+        adapters.request(
+            self.dex_module_id(),
+            DexExecuteMsg::CreateDca {
+                dex: self.dex_name(),
+                schedule,
+            },
+        )
+    }
+
+    /// Pause a schedule of the account
+    pub fn pause_dca(&self, dca_id: u64) -> AbstractSdkResult<CosmosMsg> {
+        let adapters = self.base.adapters(self.deps);
+
+        adapters.request(self.dex_module_id(), DexExecuteMsg::PauseDca { dca_id })
+    }
+
+    /// Resume a paused schedule of the account
+    pub fn resume_dca(&self, dca_id: u64) -> AbstractSdkResult<CosmosMsg> {
+        let adapters = self.base.adapters(self.deps);
+
+        adapters.request(self.dex_module_id(), DexExecuteMsg::ResumeDca { dca_id })
+    }
+
+    /// Cancel a schedule of the account
+    pub fn cancel_dca(&self, dca_id: u64) -> AbstractSdkResult<CosmosMsg> {
+        let adapters = self.base.adapters(self.deps);
+
+        adapters.request(self.dex_module_id(), DexExecuteMsg::CancelDca { dca_id })
+    }
+
+    /// Place a swap of a large amount that keepers execute on the DEX in slices, one per interval
+    pub fn place_twap(&self, twap: TwapOrder) -> AbstractSdkResult<CosmosMsg> {
+        let adapters = self.base.adapters(self.deps);
+
+        adapters.request(
+            self.dex_module_id(),
+            DexExecuteMsg::PlaceTwap {
+                dex: self.dex_name(),
+                twap,
+            },
+        )
+    }
+
+    /// Cancel a time-sliced order of the account
+    pub fn cancel_twap(&self, twap_id: u64) -> AbstractSdkResult<CosmosMsg> {
+        let adapters = self.base.adapters(self.deps);
+
+        adapters.request(self.dex_module_id(), DexExecuteMsg::CancelTwap { twap_id })
     }
 }
 
@@ -149,6 +342,89 @@ impl<'a, T: DexInterface> Dex<'a, T> {
             dex: Some(self.dex_name()),
             offer_asset,
             ask_asset,
+            fee_mode: self.fee_mode.clone(),
+        })?;
+        Ok(response)
+    }
+
+    /// liquidity positions of the account on the dexes of this chain
+    pub fn positions(
+        &self,
+        start_after: Option<AssetEntry>,
+        limit: Option<u8>,
+    ) -> AbstractSdkResult<PositionsResponse> {
+        let response: PositionsResponse = self.query(DexQueryMsg::Positions {
+            account: self.base.proxy_address(self.deps)?.to_string(),
+            start_after,
+            limit,
+        })?;
+        Ok(response)
+    }
+
+    /// open orders of the account
+    pub fn orders(
+        &self,
+        start_after: Option<u64>,
+        limit: Option<u8>,
+    ) -> AbstractSdkResult<OrdersResponse> {
+        let response: OrdersResponse = self.query(DexQueryMsg::Orders {
+            account: self.base.proxy_address(self.deps)?.to_string(),
+            start_after,
+            limit,
+        })?;
+        Ok(response)
+    }
+
+    /// dollar-cost-averaging schedules of the account
+    pub fn dca_schedules(
+        &self,
+        start_after: Option<u64>,
+        limit: Option<u8>,
+    ) -> AbstractSdkResult<DcaSchedulesResponse> {
+        let response: DcaSchedulesResponse = self.query(DexQueryMsg::DcaSchedules {
+            account: self.base.proxy_address(self.deps)?.to_string(),
+            start_after,
+            limit,
+        })?;
+        Ok(response)
+    }
+
+    /// time-sliced orders of the account and their progress
+    pub fn twap_orders(
+        &self,
+        start_after: Option<u64>,
+        limit: Option<u8>,
+    ) -> AbstractSdkResult<TwapOrdersResponse> {
+        let response: TwapOrdersResponse = self.query(DexQueryMsg::TwapOrders {
+            account: self.base.proxy_address(self.deps)?.to_string(),
+            start_after,
+            limit,
+        })?;
+        Ok(response)
+    }
+
+    /// underlying assets of the LP tokens, valued in the quote asset if provided
+    pub fn lp_value(
+        &self,
+        lp_token: AssetEntry,
+        amount: impl Into<Uint128>,
+        quote_asset: Option<AssetEntry>,
+    ) -> AbstractSdkResult<LpValueResponse> {
+        let response: LpValueResponse = self.query(DexQueryMsg::LpValue {
+            dex: self.dex_name(),
+            lp_token,
+            amount: amount.into(),
+            quote_asset,
+        })?;
+        Ok(response)
+    }
+
+    /// expected balance changes of the account from executing the action
+    pub fn dry_run(&self, action: DexAction) -> AbstractSdkResult<DryRunResponse> {
+        let response: DryRunResponse = self.query(DexQueryMsg::DryRun {
+            dex: self.dex_name(),
+            action,
+            account: self.base.proxy_address(self.deps)?.to_string(),
         })?;
         Ok(response)
     }
@@ -161,7 +437,7 @@ mod test {
     use abstract_core::adapter::AdapterRequestMsg;
     use abstract_sdk::mock_module::MockModule;
     use cosmwasm_std::testing::mock_dependencies;
-    use cosmwasm_std::wasm_execute;
+    use cosmwasm_std::{wasm_execute, Timestamp};
     use speculoos::prelude::*;
 
     fn expected_request_with_test_proxy(request: DexExecuteMsg) -> ExecuteMsg {
@@ -194,7 +470,11 @@ mod test {
                 ask_asset: ask_asset.clone(),
                 max_spread,
                 belief_price,
+                recipient: None,
+                fee_mode: FeeMode::OfferAsset,
             },
+            return_proceeds: false,
+            deadline: None,
         });
 
         let actual = dex.swap(offer_asset, ask_asset, max_spread, belief_price);
@@ -215,6 +495,101 @@ mod test {
         assert_that!(actual).is_equal_to(expected);
     }
 
+    #[test]
+    fn swap_to_msg() {
+        let mut deps = mock_dependencies();
+        deps.querier = abstract_testing::mock_querier();
+        let stub = MockModule::new();
+        let dex_name = "junoswap".to_string();
+
+        let dex = stub
+            .dex(deps.as_ref(), dex_name.clone())
+            .with_module_id(abstract_testing::prelude::TEST_MODULE_ID);
+
+        let offer_asset = OfferAsset::new("juno", 1000u128);
+        let ask_asset = AssetEntry::new("uusd");
+        let recipient = Recipient::Account(2);
+
+        let expected = expected_request_with_test_proxy(DexExecuteMsg::Action {
+            dex: dex_name,
+            action: DexAction::Swap {
+                offer_asset: offer_asset.clone(),
+                ask_asset: ask_asset.clone(),
+                max_spread: None,
+                belief_price: None,
+                recipient: Some(recipient.clone()),
+                fee_mode: FeeMode::OfferAsset,
+            },
+            return_proceeds: false,
+            deadline: None,
+        });
+
+        let actual = dex.swap_to(offer_asset, ask_asset, None, None, recipient);
+
+        assert_that!(actual).is_ok();
+
+        let actual = match actual.unwrap() {
+            CosmosMsg::Wasm(msg) => msg,
+            _ => panic!("expected wasm msg"),
+        };
+        let expected = wasm_execute(
+            abstract_testing::prelude::TEST_MODULE_ADDRESS,
+            &expected,
+            vec![],
+        )
+        .unwrap();
+
+        assert_that!(actual).is_equal_to(expected);
+    }
+
+    #[test]
+    fn swap_with_deadline_msg() {
+        let mut deps = mock_dependencies();
+        deps.querier = abstract_testing::mock_querier();
+        let stub = MockModule::new();
+        let dex_name = "junoswap".to_string();
+        let deadline = Expiration::AtTime(Timestamp::from_seconds(1_700_000_000));
+
+        let dex = stub
+            .dex(deps.as_ref(), dex_name.clone())
+            .with_module_id(abstract_testing::prelude::TEST_MODULE_ID)
+            .with_deadline(deadline);
+
+        let offer_asset = OfferAsset::new("juno", 1000u128);
+        let ask_asset = AssetEntry::new("uusd");
+
+        let expected = expected_request_with_test_proxy(DexExecuteMsg::Action {
+            dex: dex_name,
+            action: DexAction::Swap {
+                offer_asset: offer_asset.clone(),
+                ask_asset: ask_asset.clone(),
+                max_spread: None,
+                belief_price: None,
+                recipient: None,
+                fee_mode: FeeMode::OfferAsset,
+            },
+            return_proceeds: false,
+            deadline: Some(deadline),
+        });
+
+        let actual = dex.swap(offer_asset, ask_asset, None, None);
+
+        assert_that!(actual).is_ok();
+
+        let actual = match actual.unwrap() {
+            CosmosMsg::Wasm(msg) => msg,
+            _ => panic!("expected wasm msg"),
+        };
+        let expected = wasm_execute(
+            abstract_testing::prelude::TEST_MODULE_ADDRESS,
+            &expected,
+            vec![],
+        )
+        .unwrap();
+
+        assert_that!(actual).is_equal_to(expected);
+    }
+
     #[test]
     fn custom_swap_msg() {
         let mut deps = mock_dependencies();
@@ -239,6 +614,8 @@ mod test {
                 max_spread,
                 router: router.clone(),
             },
+            return_proceeds: false,
+            deadline: None,
         });
 
         let actual = dex.custom_swap(offer_assets, ask_assets, max_spread, router);
@@ -278,7 +655,10 @@ mod test {
             action: DexAction::ProvideLiquidity {
                 assets: assets.clone(),
                 max_spread,
+                recipient: None,
             },
+            return_proceeds: false,
+            deadline: None,
         });
 
         let actual = dex.provide_liquidity(assets, max_spread);
@@ -299,6 +679,52 @@ mod test {
         assert_that!(actual).is_equal_to(expected);
     }
 
+    #[test]
+    fn create_pool_msg() {
+        let mut deps = mock_dependencies();
+        deps.querier = abstract_testing::mock_querier();
+        let stub = MockModule::new();
+        let dex_name = "junoswap".to_string();
+
+        let dex = stub
+            .dex(deps.as_ref(), dex_name.clone())
+            .with_module_id(abstract_testing::prelude::TEST_MODULE_ID);
+
+        let assets = vec![AssetEntry::new("taco"), AssetEntry::new("juno")];
+        let initial_liquidity = Some(vec![
+            OfferAsset::new("taco", 1000u128),
+            OfferAsset::new("juno", 1000u128),
+        ]);
+
+        let expected = expected_request_with_test_proxy(DexExecuteMsg::Action {
+            dex: dex_name,
+            action: DexAction::CreatePool {
+                assets: assets.clone(),
+                pool_type: PoolType::ConstantProduct,
+                initial_liquidity: initial_liquidity.clone(),
+            },
+            return_proceeds: false,
+            deadline: None,
+        });
+
+        let actual = dex.create_pool(assets, PoolType::ConstantProduct, initial_liquidity);
+
+        assert_that!(actual).is_ok();
+
+        let actual = match actual.unwrap() {
+            CosmosMsg::Wasm(msg) => msg,
+            _ => panic!("expected wasm msg"),
+        };
+        let expected = wasm_execute(
+            abstract_testing::prelude::TEST_MODULE_ADDRESS,
+            &expected,
+            vec![],
+        )
+        .unwrap();
+
+        assert_that!(actual).is_equal_to(expected);
+    }
+
     #[test]
     fn provide_liquidity_symmetric_msg() {
         let mut deps = mock_dependencies();
@@ -319,7 +745,10 @@ mod test {
             action: DexAction::ProvideLiquiditySymmetric {
                 offer_asset: offer.clone(),
                 paired_assets: paired.clone(),
+                max_paired_assets: None,
             },
+            return_proceeds: false,
+            deadline: None,
         });
 
         let actual = dex.provide_liquidity_symmetric(offer, paired);
@@ -359,7 +788,10 @@ mod test {
             action: DexAction::WithdrawLiquidity {
                 lp_token: lp_token.clone(),
                 amount: withdraw_amount,
+                recipient: None,
             },
+            return_proceeds: false,
+            deadline: None,
         });
 
         let actual = dex.withdraw_liquidity(lp_token, withdraw_amount);
@@ -379,4 +811,147 @@ mod test {
 
         assert_that!(actual).is_equal_to(expected);
     }
+
+    #[test]
+    fn withdraw_liquidity_single_asset_msg() {
+        let mut deps = mock_dependencies();
+        deps.querier = abstract_testing::mock_querier();
+        let stub = MockModule::new();
+        let dex_name = "junoswap".to_string();
+
+        let dex = stub
+            .dex(deps.as_ref(), dex_name.clone())
+            .with_module_id(abstract_testing::prelude::TEST_MODULE_ID);
+
+        let lp_token = AssetEntry::new("taco");
+        let withdraw_amount: Uint128 = 1000u128.into();
+        let ask_asset = AssetEntry::new("juno");
+
+        let expected = expected_request_with_test_proxy(DexExecuteMsg::Action {
+            dex: dex_name,
+            action: DexAction::WithdrawLiquiditySingleAsset {
+                lp_token: lp_token.clone(),
+                amount: withdraw_amount,
+                ask_asset: ask_asset.clone(),
+                recipient: None,
+            },
+            return_proceeds: false,
+            deadline: None,
+        });
+
+        let actual = dex.withdraw_liquidity_single_asset(lp_token, withdraw_amount, ask_asset);
+
+        assert_that!(actual).is_ok();
+
+        let actual = match actual.unwrap() {
+            CosmosMsg::Wasm(msg) => msg,
+            _ => panic!("expected wasm msg"),
+        };
+        let expected = wasm_execute(
+            abstract_testing::prelude::TEST_MODULE_ADDRESS,
+            &expected,
+            vec![],
+        )
+        .unwrap();
+
+        assert_that!(actual).is_equal_to(expected);
+    }
+
+    #[test]
+    fn raw_swap_msg() {
+        let mut deps = mock_dependencies();
+        deps.querier = abstract_testing::mock_querier();
+        let stub = MockModule::new();
+        let dex_name = "junoswap".to_string();
+
+        let dex = stub
+            .dex(deps.as_ref(), dex_name.clone())
+            .with_module_id(abstract_testing::prelude::TEST_MODULE_ID);
+
+        let pool = UncheckedPoolAddress::contract("juno_new_pool");
+        let offer_asset = AssetUnchecked::native("ujuno", 1000u128);
+        let ask_asset = AssetInfoUnchecked::cw20("juno_new_token");
+        let max_spread = Some(Decimal::percent(1));
+
+        let expected = expected_request_with_test_proxy(DexExecuteMsg::Action {
+            dex: dex_name,
+            action: DexAction::RawSwap {
+                pool: pool.clone(),
+                offer_asset: offer_asset.clone(),
+                ask_asset: ask_asset.clone(),
+                max_spread,
+                belief_price: None,
+            },
+            return_proceeds: false,
+            deadline: None,
+        });
+
+        let actual = dex.raw_swap(pool, offer_asset, ask_asset, max_spread, None);
+
+        assert_that!(actual).is_ok();
+
+        let actual = match actual.unwrap() {
+            CosmosMsg::Wasm(msg) => msg,
+            _ => panic!("expected wasm msg"),
+        };
+        let expected = wasm_execute(
+            abstract_testing::prelude::TEST_MODULE_ADDRESS,
+            &expected,
+            vec![],
+        )
+        .unwrap();
+
+        assert_that!(actual).is_equal_to(expected);
+    }
+
+    #[test]
+    fn batch_msg() {
+        let mut deps = mock_dependencies();
+        deps.querier = abstract_testing::mock_querier();
+        let stub = MockModule::new();
+        let dex_name = "junoswap".to_string();
+
+        let dex = stub
+            .dex(deps.as_ref(), dex_name.clone())
+            .with_module_id(abstract_testing::prelude::TEST_MODULE_ID);
+
+        let swap = DexAction::Swap {
+            offer_asset: OfferAsset::new("juno", 1000u128),
+            ask_asset: AssetEntry::new("uusd"),
+            max_spread: None,
+            belief_price: None,
+            recipient: None,
+            fee_mode: FeeMode::OfferAsset,
+        };
+        // provide the output of the swap
+        let provide = DexAction::ProvideLiquiditySymmetric {
+            offer_asset: OfferAsset::new("uusd", 0u128),
+            paired_assets: vec![AssetEntry::new("juno")],
+            max_paired_assets: None,
+        };
+
+        let expected = expected_request_with_test_proxy(DexExecuteMsg::Batch {
+            actions: vec![
+                (dex_name.clone(), swap.clone()),
+                (dex_name, provide.clone()),
+            ],
+        });
+
+        let actual = dex.batch(vec![swap, provide]);
+
+        assert_that!(actual).is_ok();
+
+        let actual = match actual.unwrap() {
+            CosmosMsg::Wasm(msg) => msg,
+            _ => panic!("expected wasm msg"),
+        };
+        let expected = wasm_execute(
+            abstract_testing::prelude::TEST_MODULE_ADDRESS,
+            &expected,
+            vec![],
+        )
+        .unwrap();
+
+        assert_that!(actual).is_equal_to(expected);
+    }
 }
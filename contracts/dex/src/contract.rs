@@ -1,20 +1,78 @@
 use crate::handlers;
 use crate::EXCHANGE;
 
-use crate::msg::{DexExecuteMsg, DexInstantiateMsg, DexQueryMsg};
-use abstract_adapter::{export_endpoints, AdapterContract};
+use crate::msg::{DexExecuteMsg, DexInstantiateMsg, DexKeeperMsg, DexQueryMsg};
+use abstract_adapter::AdapterContract;
 use abstract_dex_adapter_traits::DexError;
 use cosmwasm_std::Response;
+#[cfg(feature = "export")]
+use {
+    crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg},
+    abstract_core::{abstract_ica::IbcResponseMsg, adapter},
+    abstract_sdk::base::{ExecuteEndpoint, InstantiateEndpoint, QueryEndpoint, ReplyEndpoint},
+    cosmwasm_std::{Binary, Deps, DepsMut, Env, MessageInfo, Reply},
+};
 
 pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
-pub type DexAdapter = AdapterContract<DexError, DexInstantiateMsg, DexExecuteMsg, DexQueryMsg>;
+/// Reply of a step of a batch that takes the output of the previous step
+pub const BATCH_STEP_REPLY_ID: u64 = 7547;
+/// Reply of an action whose outputs are transferred to its recipient
+pub const FORWARD_REPLY_ID: u64 = 7548;
+/// Reply of a pool creation that reports the address of the new pool
+pub const CREATE_POOL_REPLY_ID: u64 = 7549;
+/// Reply of an IBC callback forwarded to the contract that requested the action
+pub const IBC_CALLBACK_FORWARD_REPLY_ID: u64 = 7550;
+
+pub type DexAdapter =
+    AdapterContract<DexError, DexInstantiateMsg, DexExecuteMsg, DexQueryMsg, DexKeeperMsg>;
 pub type DexResult<T = Response> = Result<T, DexError>;
 
 pub const DEX_ADAPTER: DexAdapter = DexAdapter::new(EXCHANGE, CONTRACT_VERSION, None)
     .with_instantiate(handlers::instantiate_handler)
     .with_execute(handlers::execute_handler)
-    .with_query(handlers::query_handler);
+    .with_query(handlers::query_handler)
+    .with_receive(handlers::keeper_handler)
+    .with_replies(&[
+        (BATCH_STEP_REPLY_ID, handlers::batch_step_reply),
+        (FORWARD_REPLY_ID, handlers::forward_reply),
+        (CREATE_POOL_REPLY_ID, handlers::create_pool_reply),
+        (
+            IBC_CALLBACK_FORWARD_REPLY_ID,
+            handlers::ibc_callback_forward_reply,
+        ),
+    ]);
+
+// The endpoints are those of `export_endpoints!`, except that the IBC callbacks whose id carries the request
+// they belong to are routed here as the base adapter only dispatches callbacks with a registered id.
+
+#[cfg(feature = "export")]
+#[cosmwasm_std::entry_point]
+pub fn instantiate(deps: DepsMut, env: Env, info: MessageInfo, msg: InstantiateMsg) -> DexResult {
+    DEX_ADAPTER.instantiate(deps, env, info, msg)
+}
+
+#[cfg(feature = "export")]
+#[cosmwasm_std::entry_point]
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> DexResult {
+    match msg {
+        adapter::ExecuteMsg::IbcCallback(IbcResponseMsg { id, msg: ack })
+            if handlers::is_request_callback(&id) =>
+        {
+            handlers::ibc_callback_handler(deps, env, info, DEX_ADAPTER, id, ack)
+        }
+        msg => DEX_ADAPTER.execute(deps, env, info, msg),
+    }
+}
+
+#[cfg(feature = "export")]
+#[cosmwasm_std::entry_point]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> DexResult<Binary> {
+    DEX_ADAPTER.query(deps, env, msg)
+}
 
 #[cfg(feature = "export")]
-export_endpoints!(DEX_ADAPTER, DexAdapter);
+#[cosmwasm_std::entry_point]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> DexResult {
+    DEX_ADAPTER.reply(deps, env, msg)
+}
@@ -20,10 +20,10 @@ use ::{
     abstract_core::objects::PoolAddress,
     abstract_dex_adapter_traits::DexError,
     abstract_dex_adapter_traits::{coins_in_assets, cw_approve_msgs},
-    abstract_dex_adapter_traits::{DexCommand, Fee, FeeOnInput, Return, Spread},
+    abstract_dex_adapter_traits::{DexCommand, Fee, FeeOnInput, Return, Spread, TotalShare},
     abstract_sdk::cw_helpers::wasm_smart_query,
     cosmwasm_std::{
-        to_binary, wasm_execute, Coin, CosmosMsg, Decimal, Deps, Fraction, Uint128, WasmMsg,
+        to_binary, wasm_execute, Addr, Coin, CosmosMsg, Decimal, Deps, Fraction, Uint128, WasmMsg,
     },
     cw20_junoswap::{Cw20ExecuteMsg, Denom},
     cw_asset::{Asset, AssetInfo, AssetInfoBase},
@@ -31,8 +31,10 @@ use ::{
 };
 
 #[cfg(feature = "juno")]
-impl DexCommand for JunoSwap {
-    fn swap(
+impl JunoSwap {
+    /// Swap on the pool, sending the returned asset to the recipient or the sender
+    #[allow(clippy::too_many_arguments)]
+    fn swap_msgs(
         &self,
         deps: Deps,
         pool_id: PoolAddress,
@@ -40,6 +42,7 @@ impl DexCommand for JunoSwap {
         ask_asset: AssetInfo,
         belief_price: Option<Decimal>,
         max_spread: Option<Decimal>,
+        recipient: Option<Addr>,
     ) -> Result<Vec<CosmosMsg>, DexError> {
         let pair_address = pool_id.expect_contract()?;
 
@@ -76,11 +79,20 @@ impl DexCommand for JunoSwap {
             }
         };
 
-        let swap_msg = ExecuteMsg::Swap {
-            input_token: offer_token,
-            input_amount: offer_asset.amount,
-            min_output: min_out,
-            expiration: None,
+        let swap_msg = match recipient {
+            None => ExecuteMsg::Swap {
+                input_token: offer_token,
+                input_amount: offer_asset.amount,
+                min_output: min_out,
+                expiration: None,
+            },
+            Some(recipient) => ExecuteMsg::SwapAndSendTo {
+                input_token: offer_token,
+                input_amount: offer_asset.amount,
+                recipient: recipient.into_string(),
+                min_token: min_out,
+                expiration: None,
+            },
         };
         let msgs = match &offer_asset.info {
             AssetInfoBase::Cw20(token_addr) => {
@@ -107,6 +119,50 @@ impl DexCommand for JunoSwap {
         }?;
         Ok(msgs)
     }
+}
+
+#[cfg(feature = "juno")]
+impl DexCommand for JunoSwap {
+    fn swap(
+        &self,
+        deps: Deps,
+        pool_id: PoolAddress,
+        offer_asset: Asset,
+        ask_asset: AssetInfo,
+        belief_price: Option<Decimal>,
+        max_spread: Option<Decimal>,
+    ) -> Result<Vec<CosmosMsg>, DexError> {
+        self.swap_msgs(
+            deps,
+            pool_id,
+            offer_asset,
+            ask_asset,
+            belief_price,
+            max_spread,
+            None,
+        )
+    }
+
+    fn swap_to(
+        &self,
+        deps: Deps,
+        pool_id: PoolAddress,
+        offer_asset: Asset,
+        ask_asset: AssetInfo,
+        belief_price: Option<Decimal>,
+        max_spread: Option<Decimal>,
+        recipient: Addr,
+    ) -> Result<Vec<CosmosMsg>, DexError> {
+        self.swap_msgs(
+            deps,
+            pool_id,
+            offer_asset,
+            ask_asset,
+            belief_price,
+            max_spread,
+            Some(recipient),
+        )
+    }
 
     fn provide_liquidity(
         &self,
@@ -299,6 +355,37 @@ impl DexCommand for JunoSwap {
             };
         Ok((return_amount, spread_amount, Uint128::zero(), true))
     }
+
+    fn pool_reserves(
+        &self,
+        deps: Deps,
+        pool_id: PoolAddress,
+    ) -> Result<(Vec<Asset>, TotalShare), DexError> {
+        let pair_address = pool_id.expect_contract()?;
+        let pair_config: InfoResponse = deps.querier.query(&wasm_smart_query(
+            pair_address.to_string(),
+            &QueryMsg::Info {},
+        )?)?;
+        let assets = vec![
+            Asset::new(
+                denom_asset_info(&pair_config.token1_denom),
+                pair_config.token1_reserve,
+            ),
+            Asset::new(
+                denom_asset_info(&pair_config.token2_denom),
+                pair_config.token2_reserve,
+            ),
+        ];
+        Ok((assets, pair_config.lp_token_supply))
+    }
+}
+
+#[cfg(feature = "juno")]
+fn denom_asset_info(denom: &Denom) -> AssetInfo {
+    match denom {
+        Denom::Native(denom_name) => AssetInfo::native(denom_name),
+        Denom::Cw20(denom_addr) => AssetInfo::cw20(denom_addr.clone()),
+    }
 }
 
 #[cfg(feature = "juno")]
@@ -23,9 +23,10 @@ use ::{
     },
     abstract_sdk::cw_helpers::wasm_smart_query,
     cosmwasm_std::{
-        wasm_execute, Addr, Coin, CosmosMsg, Decimal, Decimal256, Deps, StdError, StdResult,
-        Uint128,
+        to_binary, wasm_execute, Addr, Coin, CosmosMsg, Decimal, Decimal256, Deps, StdError,
+        StdResult, Uint128,
     },
+    cw20::Cw20ExecuteMsg,
     cw_asset::{Asset, AssetInfo, AssetInfoBase},
     kujira::{
         bow::{
@@ -36,6 +37,81 @@ use ::{
     },
 };
 
+/// Fin pair and bow pool addresses of the pool
+#[cfg(feature = "kujira")]
+fn separate_addresses(pool_id: PoolAddress) -> Result<(Addr, Addr), DexError> {
+    match pool_id {
+        PoolAddress::SeparateAddresses { swap, liquidity } => Ok((swap, liquidity)),
+        pool_id => Err(DexError::InvalidPoolAddress(
+            KUJIRA.to_string(),
+            "separate swap and liquidity addresses".to_string(),
+            format!("{pool_id:?}"),
+        )),
+    }
+}
+
+/// Bow pools only hold native denoms
+#[cfg(feature = "kujira")]
+fn assert_native(assets: &[Asset]) -> Result<(), DexError> {
+    match assets
+        .iter()
+        .find(|asset| !matches!(asset.info, AssetInfo::Native(_)))
+    {
+        Some(asset) => Err(DexError::UnsupportedAssetType(asset.info.to_string())),
+        None => Ok(()),
+    }
+}
+
+/// Swap on the fin pair, sending the returned asset to `to` or the sender
+#[cfg(feature = "kujira")]
+fn swap_msgs(
+    pool_id: PoolAddress,
+    offer_asset: Asset,
+    belief_price: Option<Decimal>,
+    max_spread: Option<Decimal>,
+    to: Option<Addr>,
+) -> Result<Vec<CosmosMsg>, DexError> {
+    let (fin_pair_address, _) = separate_addresses(pool_id)?;
+
+    let belief_price = belief_price.map(decimal2decimal256).transpose()?;
+    let max_spread = max_spread.map(decimal2decimal256).transpose()?;
+
+    let swap_msg: CosmosMsg = match &offer_asset.info {
+        AssetInfo::Native(_) => wasm_execute(
+            fin_pair_address.to_string(),
+            &fin::ExecuteMsg::Swap {
+                offer_asset: Some(Coin::try_from(&offer_asset)?),
+                belief_price,
+                max_spread,
+                to,
+                callback: None,
+            },
+            vec![offer_asset.clone().try_into()?],
+        )?
+        .into(),
+        AssetInfo::Cw20(token_addr) => {
+            // the offered amount is taken from the cw20 receive message
+            let hook_msg = fin::ExecuteMsg::Swap {
+                offer_asset: None,
+                belief_price,
+                max_spread,
+                to,
+                callback: None,
+            };
+            // Call swap on pair through cw20 Send
+            let send_msg = Cw20ExecuteMsg::Send {
+                contract: fin_pair_address.to_string(),
+                amount: offer_asset.amount,
+                msg: to_binary(&hook_msg)?,
+            };
+            wasm_execute(token_addr, &send_msg, vec![])?.into()
+        }
+        _ => return Err(DexError::UnsupportedAssetType(offer_asset.info.to_string())),
+    };
+    Ok(vec![swap_msg])
+}
+
+// FIN pairs and BOW pools are created through governance, so `create_pool` is not implemented
 #[cfg(feature = "kujira")]
 impl DexCommand for Kujira {
     fn swap(
@@ -47,35 +123,26 @@ impl DexCommand for Kujira {
         belief_price: Option<Decimal>,
         max_spread: Option<Decimal>,
     ) -> Result<Vec<CosmosMsg>, DexError> {
-        let fin_pair_address: Addr = match pool_id {
-            PoolAddress::SeparateAddresses { swap, liquidity: _ } => swap,
-            _ => panic!("invalid address"),
-        };
+        swap_msgs(pool_id, offer_asset, belief_price, max_spread, None)
+    }
 
-        let swap_msg: Vec<CosmosMsg> = match &offer_asset.info {
-            AssetInfo::Native(_) => vec![wasm_execute(
-                fin_pair_address.to_string(),
-                &fin::ExecuteMsg::Swap {
-                    offer_asset: Some(Coin::try_from(&offer_asset)?),
-                    belief_price: if let Some(belief_price) = belief_price {
-                        Some(decimal2decimal256(belief_price)?)
-                    } else {
-                        None
-                    },
-                    max_spread: if let Some(max_spread) = max_spread {
-                        Some(decimal2decimal256(max_spread)?)
-                    } else {
-                        None
-                    },
-                    to: None,
-                    callback: None,
-                },
-                vec![offer_asset.clone().try_into()?],
-            )?
-            .into()],
-            _ => panic!("unsupported asset"),
-        };
-        Ok(swap_msg)
+    fn swap_to(
+        &self,
+        _deps: Deps,
+        pool_id: PoolAddress,
+        offer_asset: Asset,
+        _ask_asset: AssetInfo,
+        belief_price: Option<Decimal>,
+        max_spread: Option<Decimal>,
+        recipient: Addr,
+    ) -> Result<Vec<CosmosMsg>, DexError> {
+        swap_msgs(
+            pool_id,
+            offer_asset,
+            belief_price,
+            max_spread,
+            Some(recipient),
+        )
     }
 
     fn provide_liquidity(
@@ -85,16 +152,8 @@ impl DexCommand for Kujira {
         mut offer_assets: Vec<Asset>,
         max_spread: Option<Decimal>,
     ) -> Result<Vec<CosmosMsg>, DexError> {
-        let fin_pair_address: Addr;
-        let bow_pair_address: Addr;
-
-        match pool_id {
-            PoolAddress::SeparateAddresses { swap, liquidity } => {
-                fin_pair_address = swap;
-                bow_pair_address = liquidity;
-            }
-            _ => panic!("invalid address"),
-        }
+        let (fin_pair_address, bow_pair_address) = separate_addresses(pool_id)?;
+        assert_native(&offer_assets)?;
         let mut msgs = vec![];
 
         // We know that (+)two assets were provided because it's a requirement to resolve the pool
@@ -173,16 +232,13 @@ impl DexCommand for Kujira {
         offer_asset: Asset,
         paired_assets: Vec<AssetInfo>,
     ) -> Result<Vec<CosmosMsg>, DexError> {
-        // unimplemented!();
-        let bow_pair_address: Addr = match pool_id {
-            PoolAddress::SeparateAddresses { swap: _, liquidity } => liquidity,
-            _ => panic!("invalid address"),
-        };
+        let (_, bow_pair_address) = separate_addresses(pool_id)?;
         let mut msgs = vec![];
 
         if paired_assets.len() > 1 {
             return Err(DexError::TooManyAssets(2));
         }
+        assert_native(&[offer_asset.clone()])?;
 
         // Pair config
         let pair_config: ConfigResponse = deps.querier.query(&wasm_smart_query(
@@ -254,10 +310,7 @@ impl DexCommand for Kujira {
         pool_id: PoolAddress,
         lp_token: Asset,
     ) -> Result<Vec<CosmosMsg>, DexError> {
-        let bow_pair_address: Addr = match pool_id {
-            PoolAddress::SeparateAddresses { swap: _, liquidity } => liquidity,
-            _ => panic!("invalid address"),
-        };
+        let (_, bow_pair_address) = separate_addresses(pool_id)?;
 
         // execute msg
         let msg = bow::market_maker::ExecuteMsg::Withdraw { callback: None };
@@ -273,10 +326,7 @@ impl DexCommand for Kujira {
         offer_asset: Asset,
         _ask_asset: AssetInfo,
     ) -> Result<(Return, Spread, Fee, FeeOnInput), DexError> {
-        let fin_pair_address: Addr = match pool_id {
-            PoolAddress::SeparateAddresses { swap, liquidity: _ } => swap,
-            _ => panic!("invalid address"),
-        };
+        let (fin_pair_address, _) = separate_addresses(pool_id)?;
         // Do simulation
         let fin::SimulationResponse {
             return_amount,
@@ -290,9 +340,9 @@ impl DexCommand for Kujira {
         )?)?;
         // commission paid in result asset
         Ok((
-            Uint128::try_from(return_amount).unwrap(),
-            Uint128::try_from(spread_amount).unwrap(),
-            Uint128::try_from(commission_amount).unwrap(),
+            Uint128::try_from(return_amount).map_err(StdError::from)?,
+            Uint128::try_from(spread_amount).map_err(StdError::from)?,
+            Uint128::try_from(commission_amount).map_err(StdError::from)?,
             false,
         ))
     }
@@ -307,6 +357,12 @@ fn cw_asset_to_kujira(asset: &Asset) -> Result<kujira::Asset, DexError> {
                 denom: denom.into(),
             },
         }),
+        AssetInfoBase::Cw20(contract_addr) => Ok(kujira::Asset {
+            amount: asset.amount,
+            info: kujira::AssetInfo::Token {
+                contract_addr: contract_addr.clone(),
+            },
+        }),
         _ => Err(DexError::UnsupportedAssetType(asset.info.to_string())),
     }
 }
@@ -321,3 +377,306 @@ pub fn decimal2decimal256(dec_value: Decimal) -> StdResult<Decimal256> {
         ))
     })
 }
+
+#[cfg(all(test, feature = "kujira"))]
+mod test {
+    use super::*;
+    use crate::adapter::DexAdapter as _;
+    use crate::contract::DEX_ADAPTER;
+    use crate::handlers::guard::guarded_swap_simulation;
+    use crate::msg::DexAction;
+    use crate::state::{MAX_PRICE_IMPACT, SWAP_FEE};
+    use abstract_core::objects::{fee::UsageFee, UncheckedPoolAddress};
+    use cosmwasm_std::{
+        coin, from_binary, testing::mock_dependencies, ContractResult, SystemResult, Uint256,
+        WasmQuery,
+    };
+    use cw_asset::{AssetInfoUnchecked, AssetUnchecked};
+    use speculoos::prelude::*;
+
+    fn pool() -> PoolAddress {
+        PoolAddress::SeparateAddresses {
+            swap: Addr::unchecked("fin"),
+            liquidity: Addr::unchecked("bow"),
+        }
+    }
+
+    fn contract_pool() -> PoolAddress {
+        PoolAddress::Contract(Addr::unchecked("pair"))
+    }
+
+    fn invalid_pool_address() -> DexError {
+        DexError::InvalidPoolAddress(
+            KUJIRA.to_string(),
+            "separate swap and liquidity addresses".to_string(),
+            format!("{:?}", contract_pool()),
+        )
+    }
+
+    fn native(denom: &str, amount: u128) -> Asset {
+        Asset::new(AssetInfo::native(denom), amount)
+    }
+
+    fn cw20(amount: u128) -> Asset {
+        Asset::new(AssetInfo::cw20(Addr::unchecked("token")), amount)
+    }
+
+    #[test]
+    fn swap_native() {
+        let deps = mock_dependencies();
+        let msgs = Kujira::default().swap(
+            deps.as_ref(),
+            pool(),
+            native("ukuji", 100),
+            AssetInfo::native("uusk"),
+            None,
+            Some(Decimal::percent(1)),
+        );
+
+        let expected: CosmosMsg = wasm_execute(
+            "fin",
+            &fin::ExecuteMsg::Swap {
+                offer_asset: Some(coin(100, "ukuji")),
+                belief_price: None,
+                max_spread: Some(Decimal256::percent(1)),
+                to: None,
+                callback: None,
+            },
+            vec![coin(100, "ukuji")],
+        )
+        .unwrap()
+        .into();
+        assert_that!(msgs).is_ok().is_equal_to(vec![expected]);
+    }
+
+    #[test]
+    fn swap_cw20_through_send_hook() {
+        let deps = mock_dependencies();
+        let msgs = Kujira::default().swap_to(
+            deps.as_ref(),
+            pool(),
+            cw20(100),
+            AssetInfo::native("ukuji"),
+            None,
+            None,
+            Addr::unchecked("recipient"),
+        );
+
+        let hook_msg = fin::ExecuteMsg::Swap {
+            offer_asset: None,
+            belief_price: None,
+            max_spread: None,
+            to: Some(Addr::unchecked("recipient")),
+            callback: None,
+        };
+        let expected: CosmosMsg = wasm_execute(
+            "token",
+            &Cw20ExecuteMsg::Send {
+                contract: "fin".to_string(),
+                amount: 100u128.into(),
+                msg: to_binary(&hook_msg).unwrap(),
+            },
+            vec![],
+        )
+        .unwrap()
+        .into();
+        assert_that!(msgs).is_ok().is_equal_to(vec![expected]);
+    }
+
+    #[test]
+    fn swap_cw20_through_adapter() {
+        let mut deps = mock_dependencies();
+        let proxy = Addr::unchecked("proxy");
+        let fee =
+            UsageFee::new(deps.as_ref().api, Decimal::zero(), Addr::unchecked("fee")).unwrap();
+        SWAP_FEE.save(deps.as_mut().storage, &fee).unwrap();
+        // the price guard makes the adapter simulate the swap before executing it
+        MAX_PRICE_IMPACT
+            .save(deps.as_mut().storage, &proxy, &Decimal::percent(5))
+            .unwrap();
+        deps.querier.update_wasm(|query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "fin" => {
+                let fin::QueryMsg::Simulation { offer_asset } = from_binary(msg).unwrap() else {
+                    panic!("unexpected fin query");
+                };
+                assert_eq!(
+                    offer_asset.info,
+                    kujira::AssetInfo::Token {
+                        contract_addr: Addr::unchecked("token")
+                    }
+                );
+                let simulation = fin::SimulationResponse {
+                    return_amount: Uint256::from(95u128),
+                    spread_amount: Uint256::from(4u128),
+                    commission_amount: Uint256::from(1u128),
+                };
+                SystemResult::Ok(ContractResult::Ok(to_binary(&simulation).unwrap()))
+            }
+            _ => panic!("unexpected query {query:?}"),
+        });
+
+        let action = DexAction::RawSwap {
+            pool: UncheckedPoolAddress::SeparateAddresses {
+                swap: "fin".to_string(),
+                liquidity: "bow".to_string(),
+            },
+            offer_asset: AssetUnchecked::cw20("token", 100u128),
+            ask_asset: AssetInfoUnchecked::native("ukuji"),
+            max_spread: Some(Decimal::percent(1)),
+            belief_price: None,
+        };
+        let simulation = guarded_swap_simulation(
+            deps.as_ref(),
+            &DEX_ADAPTER,
+            &Kujira::default(),
+            &proxy,
+            &action,
+        )
+        .unwrap()
+        .unwrap();
+        assert_that!(simulation.return_amount).is_equal_to(Uint128::new(95));
+
+        let resolved = DEX_ADAPTER
+            .resolve_dex_action(deps.as_ref(), action, &Kujira::default())
+            .unwrap();
+        let expected = swap_msgs(pool(), cw20(100), None, Some(Decimal::percent(1)), None).unwrap();
+        assert_that!(resolved.msgs[0]).is_equal_to(&expected[0]);
+    }
+
+    #[test]
+    fn swap_invalid_pool_address() {
+        let deps = mock_dependencies();
+        let res = Kujira::default().swap(
+            deps.as_ref(),
+            contract_pool(),
+            native("ukuji", 100),
+            AssetInfo::native("uusk"),
+            None,
+            None,
+        );
+
+        assert_that!(res)
+            .is_err()
+            .is_equal_to(invalid_pool_address());
+    }
+
+    #[test]
+    fn provide_liquidity_invalid_pool_address() {
+        let deps = mock_dependencies();
+        let res = Kujira::default().provide_liquidity(
+            deps.as_ref(),
+            contract_pool(),
+            vec![native("ukuji", 100), native("uusk", 100)],
+            None,
+        );
+
+        assert_that!(res)
+            .is_err()
+            .is_equal_to(invalid_pool_address());
+    }
+
+    #[test]
+    fn provide_liquidity_cw20() {
+        let deps = mock_dependencies();
+        let res = Kujira::default().provide_liquidity(
+            deps.as_ref(),
+            pool(),
+            vec![native("ukuji", 100), cw20(100)],
+            None,
+        );
+
+        assert_that!(res)
+            .is_err()
+            .is_equal_to(DexError::UnsupportedAssetType(cw20(0).info.to_string()));
+    }
+
+    #[test]
+    fn provide_liquidity_symmetric_invalid_pool_address() {
+        let deps = mock_dependencies();
+        let res = Kujira::default().provide_liquidity_symmetric(
+            deps.as_ref(),
+            contract_pool(),
+            native("ukuji", 100),
+            vec![AssetInfo::native("uusk")],
+        );
+
+        assert_that!(res)
+            .is_err()
+            .is_equal_to(invalid_pool_address());
+    }
+
+    #[test]
+    fn provide_liquidity_symmetric_cw20() {
+        let deps = mock_dependencies();
+        let res = Kujira::default().provide_liquidity_symmetric(
+            deps.as_ref(),
+            pool(),
+            cw20(100),
+            vec![AssetInfo::native("ukuji")],
+        );
+
+        assert_that!(res)
+            .is_err()
+            .is_equal_to(DexError::UnsupportedAssetType(cw20(0).info.to_string()));
+    }
+
+    #[test]
+    fn withdraw_liquidity() {
+        let deps = mock_dependencies();
+        let msgs = Kujira::default().withdraw_liquidity(
+            deps.as_ref(),
+            pool(),
+            native("factory/bow/ulp", 100),
+        );
+
+        let expected: CosmosMsg = wasm_execute(
+            "bow",
+            &bow::market_maker::ExecuteMsg::Withdraw { callback: None },
+            vec![coin(100, "factory/bow/ulp")],
+        )
+        .unwrap()
+        .into();
+        assert_that!(msgs).is_ok().is_equal_to(vec![expected]);
+    }
+
+    #[test]
+    fn withdraw_liquidity_invalid_pool_address() {
+        let deps = mock_dependencies();
+        let res = Kujira::default().withdraw_liquidity(
+            deps.as_ref(),
+            contract_pool(),
+            native("factory/bow/ulp", 100),
+        );
+
+        assert_that!(res)
+            .is_err()
+            .is_equal_to(invalid_pool_address());
+    }
+
+    #[test]
+    fn simulate_swap_invalid_pool_address() {
+        let deps = mock_dependencies();
+        let res = Kujira::default().simulate_swap(
+            deps.as_ref(),
+            contract_pool(),
+            native("ukuji", 100),
+            AssetInfo::native("uusk"),
+        );
+
+        assert_that!(res)
+            .is_err()
+            .is_equal_to(invalid_pool_address());
+    }
+
+    #[test]
+    fn cw20_to_kujira_asset() {
+        assert_that!(cw_asset_to_kujira(&cw20(100)))
+            .is_ok()
+            .is_equal_to(kujira::Asset {
+                amount: Uint128::new(100),
+                info: kujira::AssetInfo::Token {
+                    contract_addr: Addr::unchecked("token"),
+                },
+            });
+    }
+}
@@ -16,11 +16,13 @@ impl Identify for Terraswap {
 
 #[cfg(feature = "terra")]
 use ::{
-    abstract_core::objects::PoolAddress,
+    abstract_core::objects::{PoolAddress, PoolType},
     abstract_dex_adapter_traits::{coins_in_assets, cw_approve_msgs},
-    abstract_dex_adapter_traits::{DexCommand, DexError, Fee, FeeOnInput, Return, Spread},
+    abstract_dex_adapter_traits::{
+        DexCommand, DexError, Fee, FeeOnInput, Return, Spread, TotalShare,
+    },
     abstract_sdk::cw_helpers::wasm_smart_query,
-    cosmwasm_std::{to_binary, wasm_execute, CosmosMsg, Decimal, Deps},
+    cosmwasm_std::{to_binary, wasm_execute, Addr, CosmosMsg, Decimal, Deps},
     cw20::Cw20ExecuteMsg,
     cw_asset::{Asset, AssetInfo, AssetInfoBase},
     terraswap::pair::{PoolResponse, SimulationResponse},
@@ -37,33 +39,26 @@ impl DexCommand for Terraswap {
         belief_price: Option<Decimal>,
         max_spread: Option<Decimal>,
     ) -> Result<Vec<CosmosMsg>, DexError> {
-        let pair_address = pool_id.expect_contract()?;
-
-        let proxy_msg = if let AssetInfoBase::Cw20(token_addr) = &offer_asset.info {
-            let hook_msg = terraswap::pair::Cw20HookMsg::Swap {
-                belief_price,
-                max_spread,
-                to: None,
-            };
-            // Call swap on pair through cw20 Send
-            let send_msg = Cw20ExecuteMsg::Send {
-                contract: pair_address.to_string(),
-                amount: offer_asset.amount,
-                msg: to_binary(&hook_msg)?,
-            };
-            // call send on cw20
-            wasm_execute(token_addr, &send_msg, vec![])?
-        } else {
-            let swap_msg = terraswap::pair::ExecuteMsg::Swap {
-                offer_asset: cw_asset_to_terraswap(&offer_asset)?,
-                max_spread,
-                belief_price,
-                to: None,
-            };
-            wasm_execute(pair_address, &swap_msg, coins_in_assets(&[offer_asset]))?
-        };
+        swap_msgs(pool_id, offer_asset, belief_price, max_spread, None)
+    }
 
-        Ok(vec![proxy_msg.into()])
+    fn swap_to(
+        &self,
+        _deps: Deps,
+        pool_id: PoolAddress,
+        offer_asset: Asset,
+        _ask_asset: AssetInfo,
+        belief_price: Option<Decimal>,
+        max_spread: Option<Decimal>,
+        recipient: Addr,
+    ) -> Result<Vec<CosmosMsg>, DexError> {
+        swap_msgs(
+            pool_id,
+            offer_asset,
+            belief_price,
+            max_spread,
+            Some(recipient.into_string()),
+        )
     }
 
     fn provide_liquidity(
@@ -73,30 +68,23 @@ impl DexCommand for Terraswap {
         offer_assets: Vec<Asset>,
         max_spread: Option<Decimal>,
     ) -> Result<Vec<CosmosMsg>, DexError> {
-        let pair_address = pool_id.expect_contract()?;
-
-        if offer_assets.len() > 2 {
-            return Err(DexError::TooManyAssets(2));
-        }
-
-        let terraswap_assets = offer_assets
-            .iter()
-            .map(cw_asset_to_terraswap)
-            .collect::<Result<Vec<_>, _>>()?;
-        // execute msg
-        let msg = terraswap::pair::ExecuteMsg::ProvideLiquidity {
-            assets: [terraswap_assets[0].clone(), terraswap_assets[1].clone()],
-            slippage_tolerance: max_spread,
-            receiver: None,
-        };
-        // approval msgs for cw20 tokens (if present)
-        let mut msgs = cw_approve_msgs(&offer_assets, &pair_address)?;
-        let coins = coins_in_assets(&offer_assets);
-        // actual call to pair
-        let liquidity_msg = wasm_execute(pair_address, &msg, coins)?.into();
-        msgs.push(liquidity_msg);
+        provide_liquidity_msgs(pool_id, offer_assets, max_spread, None)
+    }
 
-        Ok(msgs)
+    fn provide_liquidity_to(
+        &self,
+        _deps: Deps,
+        pool_id: PoolAddress,
+        offer_assets: Vec<Asset>,
+        max_spread: Option<Decimal>,
+        recipient: Addr,
+    ) -> Result<Vec<CosmosMsg>, DexError> {
+        provide_liquidity_msgs(
+            pool_id,
+            offer_assets,
+            max_spread,
+            Some(recipient.into_string()),
+        )
     }
 
     fn provide_liquidity_symmetric(
@@ -204,6 +192,128 @@ impl DexCommand for Terraswap {
         // commission paid in result asset
         Ok((return_amount, spread_amount, commission_amount, false))
     }
+
+    fn create_pool(
+        &self,
+        _deps: Deps,
+        factory: Addr,
+        assets: Vec<Asset>,
+        pool_type: PoolType,
+    ) -> Result<Vec<CosmosMsg>, DexError> {
+        if pool_type != PoolType::ConstantProduct {
+            return Err(DexError::PoolTypeNotSupported(
+                format!("{pool_type:?}"),
+                self.name().to_string(),
+            ));
+        }
+        let [asset_x, asset_y]: [Asset; 2] =
+            assets.try_into().map_err(|_| DexError::TooManyAssets(2))?;
+        let msg = terraswap::factory::ExecuteMsg::CreatePair {
+            assets: [
+                cw_asset_to_terraswap(&asset_x)?,
+                cw_asset_to_terraswap(&asset_y)?,
+            ],
+        };
+        // the factory provides the initial liquidity on behalf of the sender
+        let deposits: Vec<Asset> = [asset_x, asset_y]
+            .into_iter()
+            .filter(|asset| !asset.amount.is_zero())
+            .collect();
+        let mut msgs = cw_approve_msgs(&deposits, &factory)?;
+        let coins = coins_in_assets(&deposits);
+        msgs.push(wasm_execute(factory, &msg, coins)?.into());
+        Ok(msgs)
+    }
+
+    fn pool_reserves(
+        &self,
+        deps: Deps,
+        pool_id: PoolAddress,
+    ) -> Result<(Vec<Asset>, TotalShare), DexError> {
+        let pair_address = pool_id.expect_contract()?;
+        let pool: PoolResponse = deps.querier.query(&wasm_smart_query(
+            pair_address.to_string(),
+            &terraswap::pair::QueryMsg::Pool {},
+        )?)?;
+        let assets = pool
+            .assets
+            .iter()
+            .map(|asset| terraswap_to_cw_asset(deps, asset))
+            .collect::<Result<_, _>>()?;
+        Ok((assets, pool.total_share))
+    }
+}
+
+/// Swap on the pair, sending the returned asset to `to` or the sender
+#[cfg(feature = "terra")]
+fn swap_msgs(
+    pool_id: PoolAddress,
+    offer_asset: Asset,
+    belief_price: Option<Decimal>,
+    max_spread: Option<Decimal>,
+    to: Option<String>,
+) -> Result<Vec<CosmosMsg>, DexError> {
+    let pair_address = pool_id.expect_contract()?;
+
+    let proxy_msg = if let AssetInfoBase::Cw20(token_addr) = &offer_asset.info {
+        let hook_msg = terraswap::pair::Cw20HookMsg::Swap {
+            belief_price,
+            max_spread,
+            to,
+        };
+        // Call swap on pair through cw20 Send
+        let send_msg = Cw20ExecuteMsg::Send {
+            contract: pair_address.to_string(),
+            amount: offer_asset.amount,
+            msg: to_binary(&hook_msg)?,
+        };
+        // call send on cw20
+        wasm_execute(token_addr, &send_msg, vec![])?
+    } else {
+        let swap_msg = terraswap::pair::ExecuteMsg::Swap {
+            offer_asset: cw_asset_to_terraswap(&offer_asset)?,
+            max_spread,
+            belief_price,
+            to,
+        };
+        wasm_execute(pair_address, &swap_msg, coins_in_assets(&[offer_asset]))?
+    };
+
+    Ok(vec![proxy_msg.into()])
+}
+
+/// Provide liquidity to the pair, sending the LP tokens to `receiver` or the sender
+#[cfg(feature = "terra")]
+fn provide_liquidity_msgs(
+    pool_id: PoolAddress,
+    offer_assets: Vec<Asset>,
+    max_spread: Option<Decimal>,
+    receiver: Option<String>,
+) -> Result<Vec<CosmosMsg>, DexError> {
+    let pair_address = pool_id.expect_contract()?;
+
+    if offer_assets.len() > 2 {
+        return Err(DexError::TooManyAssets(2));
+    }
+
+    let terraswap_assets = offer_assets
+        .iter()
+        .map(cw_asset_to_terraswap)
+        .collect::<Result<Vec<_>, _>>()?;
+    // execute msg
+    let msg = terraswap::pair::ExecuteMsg::ProvideLiquidity {
+        assets: [terraswap_assets[0].clone(), terraswap_assets[1].clone()],
+        slippage_tolerance: max_spread,
+        receiver,
+    };
+    // approval msgs for cw20 tokens (if present)
+    let mut msgs = cw_approve_msgs(&offer_assets, &pair_address)?;
+    let coins = coins_in_assets(&offer_assets);
+    // actual call to pair
+    let liquidity_msg = wasm_execute(pair_address, &msg, coins)?.into();
+    msgs.push(liquidity_msg);
+
+    Ok(msgs)
 }
 
 #[cfg(feature = "terra")]
@@ -224,3 +334,14 @@ fn cw_asset_to_terraswap(asset: &Asset) -> Result<terraswap::asset::Asset, DexEr
         _ => Err(DexError::UnsupportedAssetType(asset.info.to_string())),
     }
 }
+
+#[cfg(feature = "terra")]
+fn terraswap_to_cw_asset(deps: Deps, asset: &terraswap::asset::Asset) -> Result<Asset, DexError> {
+    let info = match &asset.info {
+        terraswap::asset::AssetInfo::NativeToken { denom } => AssetInfo::native(denom),
+        terraswap::asset::AssetInfo::Token { contract_addr } => {
+            AssetInfo::cw20(deps.api.addr_validate(contract_addr)?)
+        }
+    };
+    Ok(Asset::new(info, asset.amount))
+}
@@ -1,19 +1,67 @@
 use crate::handlers::execute::exchange_resolver::is_over_ibc;
 
-use crate::contract::{DexAdapter, DexResult};
+use crate::adapter::{Forward, ResolvedAction, REMOTE_DEX_ADAPTER};
+use crate::contract::{
+    DexAdapter, DexResult, BATCH_STEP_REPLY_ID, CREATE_POOL_REPLY_ID, FORWARD_REPLY_ID,
+};
 use crate::exchanges::exchange_resolver;
-use crate::msg::{DexAction, DexExecuteMsg, DexName, IBC_DEX_ID};
-use crate::state::SWAP_FEE;
-use abstract_dex_adapter_traits::DexError;
+use crate::handlers::guard::{
+    assert_oracle_deviation, assert_price_impact, assert_twap_windows, guarded_swap_simulation,
+    has_price_guard, record_spot_price, spend_offered_assets, update_twap_pairs,
+};
+use crate::handlers::ibc_callback::{balances_request_msg, ibc_callback_id};
+use crate::msg::{
+    DcaSchedule, DexAction, DexExecuteMsg, DexName, DexQueryMsg, FeeMode, HostDexAction,
+    IbcActionInfo, IbcActionStatus, LpEntry, OfferAsset, Order, PriceSource, QueryMsg, Recipient,
+    TradeStats, TwapOrder, IBC_DEX_BALANCE_ID, IBC_DEX_ID, IBC_DEX_QUOTE_ID,
+};
+use crate::state::{
+    dca_schedules, orders, pair_stats, twap_orders, BatchState, DcaState, ForwardState, OrderState,
+    PendingIbcAction, PendingQuote, SpendState, TwapState, ACCOUNT_STATS, ASSET_LISTS, BATCH,
+    DCA_SEQUENCE, FORWARD, IBC_ACTIONS, IBC_ACTION_SEQUENCE, LP_ENTRIES, MAX_PRICE_IMPACT,
+    ORACLE_CONFIG, ORDER_SEQUENCE, PENDING_IBC_ACTIONS, PENDING_REMOTE_QUOTES,
+    REMOTE_QUOTE_SEQUENCE, SPEND_LIMITS, SWAP_FEE, TWAP_SEQUENCE,
+};
+use abstract_dex_adapter_traits::{DexCommand, DexError};
 
-use abstract_core::ibc_client::CallbackInfo;
+use abstract_core::ibc_client::{CallbackInfo, ExecuteMsg as IbcClientMsg};
+use abstract_core::ibc_host::HostAction;
 use abstract_core::objects::ans_host::AnsHost;
-use abstract_core::objects::AnsAsset;
-use abstract_sdk::{features::AbstractNameService, Execution};
-use abstract_sdk::{AccountVerification, IbcInterface, Resolve};
-use cosmwasm_std::{to_binary, Coin, Deps, DepsMut, Env, MessageInfo, Response, StdError};
+use abstract_core::objects::{
+    AccountBase, AnsAsset, AssetEntry, ContractEntry, DexAssetPairing, LpToken,
+};
+use abstract_core::proxy::ExecuteMsg as ProxyExecuteMsg;
+use abstract_sdk::cw_helpers::wasm_smart_query;
+use abstract_sdk::features::{AbstractNameService, AccountIdentification};
+use abstract_sdk::Execution;
+use abstract_sdk::{AccountVerification, Resolve};
+use cosmwasm_std::{
+    to_binary, wasm_execute, Addr, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo,
+    ReplyOn, Response, StdResult, Storage, Uint128,
+};
+use cw_asset::AssetInfo;
+use cw_utils::Expiration;
 
-const ACTION_RETRIES: u8 = 3;
+pub(crate) const ACTION_RETRIES: u8 = 3;
+
+/// Reject an action whose deadline has passed.
+/// The deadline of IBC actions is also sent to the host, which checks it again when it receives the action.
+/// Block heights of the remote chain are unknown here so they are rejected.
+pub(crate) fn assert_deadline(
+    env: &Env,
+    deadline: Option<Expiration>,
+    is_over_ibc: bool,
+) -> DexResult<()> {
+    match deadline {
+        Some(Expiration::AtHeight(height)) if is_over_ibc => {
+            Err(DexError::HeightDeadlineOverIbc(height))
+        }
+        Some(deadline) if deadline.is_expired(&env.block) => {
+            Err(DexError::DeadlineExpired(deadline))
+        }
+        _ => Ok(()),
+    }
+}
 
 pub fn execute_handler(
     deps: DepsMut,
@@ -26,11 +74,25 @@ pub fn execute_handler(
         DexExecuteMsg::Action {
             dex: dex_name,
             action,
+            return_proceeds,
+            deadline,
         } => {
             let (local_dex_name, is_over_ibc) = is_over_ibc(env.clone(), &dex_name)?;
+            assert_deadline(&env, deadline, is_over_ibc)?;
             // if exchange is on an app-chain, execute the action on the app-chain
             if is_over_ibc {
-                handle_ibc_request(&deps, info, &adapter, local_dex_name, &action)
+                handle_ibc_request(
+                    deps,
+                    env,
+                    info,
+                    &adapter,
+                    local_dex_name,
+                    action,
+                    return_proceeds,
+                    deadline,
+                )
+            } else if return_proceeds {
+                Err(DexError::ReturnProceedsNotOverIbc {})
             } else {
                 // the action can be executed on the local chain
                 handle_local_request(deps, env, info, adapter, action, local_dex_name)
@@ -60,59 +122,1039 @@ pub fn execute_handler(
             }
             Ok(Response::default())
         }
+        DexExecuteMsg::RequestRemoteQuote {
+            dex,
+            offer_asset,
+            ask_asset,
+        } => request_remote_quote(deps, env, &adapter, dex, offer_asset, ask_asset),
+        DexExecuteMsg::Batch { actions } => handle_batch(deps, env, adapter, actions),
+        DexExecuteMsg::SetMaxPriceImpact { max_price_impact } => {
+            let account = assert_account_owner(deps.as_ref(), &adapter, &info)?;
+            match max_price_impact {
+                Some(max) if max > Decimal::one() => {
+                    return Err(DexError::InvalidMaxPriceImpact(max))
+                }
+                Some(max) => MAX_PRICE_IMPACT.save(deps.storage, &account.proxy, &max)?,
+                None => MAX_PRICE_IMPACT.remove(deps.storage, &account.proxy),
+            }
+            Ok(Response::new().add_attribute("account", account.proxy))
+        }
+        DexExecuteMsg::SetOracleConfig { config } => {
+            let account = assert_account_owner(deps.as_ref(), &adapter, &info)?;
+            let old_config = ORACLE_CONFIG.may_load(deps.storage, &account.proxy)?;
+            match config {
+                Some(mut config) => {
+                    assert_twap_windows(&config)?;
+                    for (asset, source) in config.sources.iter_mut() {
+                        asset.format();
+                        if let PriceSource::Twap { quote, .. } = source {
+                            quote.format();
+                        }
+                    }
+                    update_twap_pairs(deps.storage, old_config.as_ref(), Some(&config))?;
+                    ORACLE_CONFIG.save(deps.storage, &account.proxy, &config)?
+                }
+                None => {
+                    update_twap_pairs(deps.storage, old_config.as_ref(), None)?;
+                    ORACLE_CONFIG.remove(deps.storage, &account.proxy)
+                }
+            }
+            Ok(Response::new().add_attribute("account", account.proxy))
+        }
+        DexExecuteMsg::SetSpendLimit { mut asset, limit } => {
+            let account = assert_account_owner(deps.as_ref(), &adapter, &info)?;
+            asset.format();
+            let key = (&account.proxy, &asset);
+            match limit {
+                Some(limit) if limit.window == 0 => return Err(DexError::InvalidSpendWindow {}),
+                // the current window is kept when the limit is changed
+                Some(limit) => {
+                    let state = match SPEND_LIMITS.may_load(deps.storage, key)? {
+                        Some(state) => SpendState { limit, ..state },
+                        None => SpendState {
+                            limit,
+                            window_start: env.block.time.seconds(),
+                            spent: Uint128::zero(),
+                        },
+                    };
+                    SPEND_LIMITS.save(deps.storage, key, &state)?
+                }
+                None => SPEND_LIMITS.remove(deps.storage, key),
+            }
+            Ok(Response::new()
+                .add_attribute("account", account.proxy)
+                .add_attribute("asset", asset.to_string()))
+        }
+        DexExecuteMsg::SetAssetLists { lists } => {
+            let account = assert_account_owner(deps.as_ref(), &adapter, &info)?;
+            match lists {
+                Some(mut lists) => {
+                    for asset in lists
+                        .denied
+                        .iter_mut()
+                        .chain(lists.allowed.iter_mut().flatten())
+                    {
+                        asset.format();
+                    }
+                    ASSET_LISTS.save(deps.storage, &account.proxy, &lists)?
+                }
+                None => ASSET_LISTS.remove(deps.storage, &account.proxy),
+            }
+            Ok(Response::new().add_attribute("account", account.proxy))
+        }
+        DexExecuteMsg::PlaceOrder { dex, order } => place_order(deps, env, &adapter, dex, order),
+        DexExecuteMsg::CancelOrder { order_id } => {
+            let proxy = adapter.proxy_address(deps.as_ref())?;
+            let state = orders().load(deps.storage, order_id)?;
+            if state.account.proxy != proxy {
+                return Err(DexError::NotOrderOwner(order_id));
+            }
+            orders().remove(deps.storage, order_id)?;
+            Ok(Response::new()
+                .add_attribute("account", proxy)
+                .add_attribute("order_id", order_id.to_string()))
+        }
+        DexExecuteMsg::CreateDca { dex, schedule } => {
+            create_dca(deps, env, &adapter, dex, schedule)
+        }
+        DexExecuteMsg::PauseDca { dca_id } => {
+            update_dca(deps, &adapter, dca_id, |state| state.paused = true)
+        }
+        DexExecuteMsg::ResumeDca { dca_id } => {
+            update_dca(deps, &adapter, dca_id, |state| state.paused = false)
+        }
+        DexExecuteMsg::CancelDca { dca_id } => {
+            let proxy = load_own_dca(deps.as_ref(), &adapter, dca_id)?.account.proxy;
+            dca_schedules().remove(deps.storage, dca_id)?;
+            Ok(Response::new()
+                .add_attribute("account", proxy)
+                .add_attribute("dca_id", dca_id.to_string()))
+        }
+        DexExecuteMsg::PlaceTwap { dex, twap } => place_twap(deps, env, &adapter, dex, twap),
+        DexExecuteMsg::CancelTwap { twap_id } => {
+            let proxy = adapter.proxy_address(deps.as_ref())?;
+            let state = twap_orders().load(deps.storage, twap_id)?;
+            if state.account.proxy != proxy {
+                return Err(DexError::NotTwapOwner(twap_id));
+            }
+            twap_orders().remove(deps.storage, twap_id)?;
+            Ok(Response::new()
+                .add_attribute("account", proxy)
+                .add_attribute("twap_id", twap_id.to_string()))
+        }
+    }
+}
+
+/// Store a dollar-cost-averaging schedule of the account, its swaps are executed on a local dex by keepers
+fn create_dca(
+    deps: DepsMut,
+    env: Env,
+    adapter: &DexAdapter,
+    dex: DexName,
+    mut schedule: DcaSchedule,
+) -> DexResult {
+    let now = env.block.time.seconds();
+    let (local_dex_name, is_over_ibc) = is_over_ibc(env, &dex)?;
+    if is_over_ibc {
+        return Err(DexError::ForeignDex(dex));
+    }
+    exchange_resolver::resolve_exchange(&local_dex_name)?;
+    if schedule.interval == 0 {
+        return Err(DexError::InvalidDcaInterval {});
+    }
+    assert_max_spread(schedule.max_spread)?;
+    schedule.offer_asset.name.format();
+    schedule.ask_asset.format();
+
+    let account = adapter.account_base(deps.as_ref())?;
+    let dca_id = DCA_SEQUENCE.may_load(deps.storage)?.unwrap_or_default() + 1;
+    DCA_SEQUENCE.save(deps.storage, &dca_id)?;
+    dca_schedules().save(
+        deps.storage,
+        dca_id,
+        &DcaState {
+            account: account.clone(),
+            dex: local_dex_name,
+            schedule,
+            next_execution: now,
+            paused: false,
+            executions: 0,
+        },
+    )?;
+    Ok(Response::new()
+        .add_attribute("account", account.proxy)
+        .add_attribute("dca_id", dca_id.to_string()))
+}
+
+/// Load a schedule of the account that sends the request
+fn load_own_dca(deps: Deps, adapter: &DexAdapter, dca_id: u64) -> DexResult<DcaState> {
+    let proxy = adapter.proxy_address(deps)?;
+    let state = dca_schedules().load(deps.storage, dca_id)?;
+    if state.account.proxy != proxy {
+        return Err(DexError::NotDcaOwner(dca_id));
+    }
+    Ok(state)
+}
+
+fn update_dca(
+    deps: DepsMut,
+    adapter: &DexAdapter,
+    dca_id: u64,
+    update: impl FnOnce(&mut DcaState),
+) -> DexResult {
+    let mut state = load_own_dca(deps.as_ref(), adapter, dca_id)?;
+    update(&mut state);
+    dca_schedules().save(deps.storage, dca_id, &state)?;
+    Ok(Response::new()
+        .add_attribute("account", state.account.proxy)
+        .add_attribute("dca_id", dca_id.to_string()))
+}
+
+/// Store a time-sliced order of the account, its slices are executed on a local dex by keepers
+fn place_twap(
+    deps: DepsMut,
+    env: Env,
+    adapter: &DexAdapter,
+    dex: DexName,
+    mut twap: TwapOrder,
+) -> DexResult {
+    let now = env.block.time.seconds();
+    let (local_dex_name, is_over_ibc) = is_over_ibc(env, &dex)?;
+    if is_over_ibc {
+        return Err(DexError::ForeignDex(dex));
+    }
+    exchange_resolver::resolve_exchange(&local_dex_name)?;
+    if twap.slice_amount.is_zero()
+        || twap.slice_amount > twap.offer_asset.amount
+        || twap.interval == 0
+    {
+        return Err(DexError::InvalidTwapOrder {});
     }
+    assert_max_spread(twap.max_spread)?;
+    twap.offer_asset.name.format();
+    twap.ask_asset.format();
+
+    let account = adapter.account_base(deps.as_ref())?;
+    let twap_id = TWAP_SEQUENCE.may_load(deps.storage)?.unwrap_or_default() + 1;
+    TWAP_SEQUENCE.save(deps.storage, &twap_id)?;
+    twap_orders().save(
+        deps.storage,
+        twap_id,
+        &TwapState {
+            account: account.clone(),
+            dex: local_dex_name,
+            twap,
+            offered: Uint128::zero(),
+            expected_return: Uint128::zero(),
+            slices: 0,
+            next_execution: now,
+        },
+    )?;
+    Ok(Response::new()
+        .add_attribute("account", account.proxy)
+        .add_attribute("twap_id", twap_id.to_string()))
+}
+
+/// Store an order of the account, it's executed on a local dex by a keeper
+fn place_order(
+    deps: DepsMut,
+    env: Env,
+    adapter: &DexAdapter,
+    dex: DexName,
+    mut order: Order,
+) -> DexResult {
+    let (local_dex_name, is_over_ibc) = is_over_ibc(env, &dex)?;
+    if is_over_ibc {
+        return Err(DexError::ForeignDex(dex));
+    }
+    exchange_resolver::resolve_exchange(&local_dex_name)?;
+    if order.keeper_tip >= order.offer_asset.amount {
+        return Err(DexError::InvalidKeeperTip(
+            order.keeper_tip,
+            order.offer_asset.amount,
+        ));
+    }
+    assert_max_spread(order.max_spread)?;
+    if order.trigger.price().is_zero() {
+        return Err(DexError::InvalidOrderTrigger {});
+    }
+    order.offer_asset.name.format();
+    order.ask_asset.format();
+
+    let account = adapter.account_base(deps.as_ref())?;
+    let order_id = ORDER_SEQUENCE.may_load(deps.storage)?.unwrap_or_default() + 1;
+    ORDER_SEQUENCE.save(deps.storage, &order_id)?;
+    orders().save(
+        deps.storage,
+        order_id,
+        &OrderState {
+            account: account.clone(),
+            dex: local_dex_name,
+            order,
+        },
+    )?;
+    Ok(Response::new()
+        .add_attribute("account", account.proxy)
+        .add_attribute("order_id", order_id.to_string()))
+}
+
+/// Swaps stored for keepers must limit the price they are executed at
+fn assert_max_spread(max_spread: Decimal) -> DexResult<()> {
+    if max_spread >= Decimal::one() {
+        return Err(DexError::InvalidMaxSpread(max_spread));
+    }
+    Ok(())
+}
+
+/// Only the owner of the account can change its settings
+fn assert_account_owner(
+    deps: Deps,
+    adapter: &DexAdapter,
+    info: &MessageInfo,
+) -> DexResult<AccountBase> {
+    let account = adapter.account_base(deps)?;
+    if info.sender != account.manager {
+        return Err(DexError::NotAccountOwner(info.sender.to_string()));
+    }
+    Ok(account)
 }
 
 /// Handle an adapter request that can be executed on the local chain
 fn handle_local_request(
-    deps: DepsMut,
-    _env: Env,
+    mut deps: DepsMut,
+    env: Env,
     _info: MessageInfo,
     adapter: DexAdapter,
     action: DexAction,
     exchange: String,
 ) -> DexResult {
-    let exchange = exchange_resolver::resolve_exchange(&exchange)?;
-    let (msgs, _) =
-        crate::adapter::DexAdapter::resolve_dex_action(&adapter, deps.as_ref(), action, exchange)?;
-    let proxy_msg = adapter
-        .executor(deps.as_ref())
-        .execute(msgs.into_iter().map(Into::into).collect())?;
-    Ok(Response::new().add_message(proxy_msg))
+    let creates_pool = matches!(action, DexAction::CreatePool { .. });
+    let ResolvedAction {
+        msgs,
+        forward,
+        fee_asset,
+        ..
+    } = resolve_local_action(deps.branch(), &env, &adapter, &exchange, action)?;
+    let actions = msgs.into_iter().map(Into::into).collect();
+    if creates_pool {
+        // the address of the new pool is read from the events in the reply
+        let sub_msg = adapter.executor(deps.as_ref()).execute_with_reply(
+            actions,
+            ReplyOn::Success,
+            CREATE_POOL_REPLY_ID,
+        )?;
+        return Ok(Response::new().add_submessage(sub_msg));
+    }
+    let (recipient, mut assets) = match forward {
+        Some(Forward { recipient, assets }) => (Some(recipient), assets),
+        None => (None, vec![]),
+    };
+    if let Some(fee_asset) = &fee_asset {
+        if !assets.contains(fee_asset) {
+            assets.push(fee_asset.clone());
+        }
+    }
+    if assets.is_empty() {
+        let proxy_msg = adapter.executor(deps.as_ref()).execute(actions)?;
+        return Ok(Response::new().add_message(proxy_msg));
+    }
+
+    // measure the outputs of the action to charge the fee and transfer them to the recipient in the reply
+    let account = adapter.account_base(deps.as_ref())?;
+    let balances = assets
+        .into_iter()
+        .map(|info| {
+            let balance = info.query_balance(&deps.querier, &account.proxy)?;
+            Ok((info, balance))
+        })
+        .collect::<DexResult<_>>()?;
+    FORWARD.save(
+        deps.storage,
+        &ForwardState {
+            account,
+            recipient,
+            balances,
+            fee_asset,
+        },
+    )?;
+    let sub_msg = adapter.executor(deps.as_ref()).execute_with_reply(
+        actions,
+        ReplyOn::Success,
+        FORWARD_REPLY_ID,
+    )?;
+    Ok(Response::new().add_submessage(sub_msg))
+}
+
+/// Resolve the messages of an action on a local exchange and record its statistics.
+/// Swaps are simulated first to enforce the protections of the account and to record the spot price of the pool.
+fn resolve_local_action(
+    mut deps: DepsMut,
+    env: &Env,
+    adapter: &DexAdapter,
+    exchange: &str,
+    action: DexAction,
+) -> DexResult<ResolvedAction> {
+    let exchange = exchange_resolver::resolve_exchange(exchange)?;
+    let proxy = adapter.proxy_address(deps.as_ref())?;
+    crate::adapter::DexAdapter::assert_assets_allowed(
+        adapter,
+        deps.as_ref(),
+        &proxy,
+        &action,
+        exchange.name(),
+    )?;
+    if let Some(simulation) =
+        guarded_swap_simulation(deps.as_ref(), adapter, exchange, &proxy, &action)?
+    {
+        assert_price_impact(deps.as_ref(), &proxy, &simulation)?;
+        assert_oracle_deviation(deps.as_ref(), env, adapter, &proxy, &simulation)?;
+        record_spot_price(deps.storage, env, &simulation)?;
+    }
+    spend_offered_assets(deps.branch(), env, adapter, &proxy, &action)?;
+    let resolved = crate::adapter::DexAdapter::resolve_dex_action(
+        adapter,
+        deps.as_ref(),
+        action.clone(),
+        exchange,
+    )?;
+    match action {
+        DexAction::Swap {
+            offer_asset,
+            ask_asset,
+            fee_mode,
+            ..
+        } => record_swap(
+            deps.storage,
+            &proxy,
+            exchange.name(),
+            offer_asset,
+            ask_asset,
+            fee_mode,
+        )?,
+        // LP tokens sent to another recipient aren't a position of the account
+        DexAction::ProvideLiquidity {
+            assets,
+            recipient: None,
+            ..
+        } => record_lp_deposit(deps, adapter, exchange, &proxy, assets)?,
+        DexAction::ProvideLiquiditySymmetric {
+            offer_asset,
+            paired_assets,
+            ..
+        } => {
+            if let Some(assets) =
+                symmetric_deposits(deps.as_ref(), adapter, exchange, offer_asset, paired_assets)?
+            {
+                record_lp_deposit(deps, adapter, exchange, &proxy, assets)?
+            }
+        }
+        DexAction::WithdrawLiquidity {
+            lp_token, amount, ..
+        }
+        | DexAction::WithdrawLiquiditySingleAsset {
+            lp_token, amount, ..
+        } => record_lp_withdrawal(deps, adapter, &proxy, exchange.name(), lp_token, amount)?,
+        _ => {}
+    }
+    Ok(resolved)
+}
+
+/// Add the deposited amounts to the LP entry of the account in the pool of the first two assets
+fn record_lp_deposit(
+    deps: DepsMut,
+    adapter: &DexAdapter,
+    exchange: &dyn DexCommand,
+    proxy: &Addr,
+    mut assets: Vec<AnsAsset>,
+) -> DexResult<()> {
+    for asset in assets.iter_mut() {
+        asset.name.format();
+    }
+    assets.truncate(2);
+    let dex = exchange.name();
+    // the deposit hasn't been executed yet
+    let minted = minted_lp_amount(deps.as_ref(), adapter, exchange, &assets)?;
+    let lp = LpToken::new(
+        dex,
+        assets
+            .iter()
+            .take(2)
+            .map(|a| a.name.clone())
+            .collect::<Vec<_>>(),
+    );
+    let deposits = lp
+        .assets
+        .iter()
+        .map(|name| AnsAsset::new(name.clone(), 0u128))
+        .collect();
+    let lp_token: AssetEntry = lp.into();
+
+    let key = (proxy, dex, &lp_token);
+    let mut entry = LP_ENTRIES.may_load(deps.storage, key)?.unwrap_or(LpEntry {
+        deposits,
+        entry_price: Decimal::zero(),
+        lp_amount: Uint128::zero(),
+    });
+    entry.lp_amount += minted.unwrap_or_default();
+    if let [x, y] = entry.deposits.clone().as_slice() {
+        let added_x = assets
+            .iter()
+            .find(|asset| asset.name == x.name)
+            .map(|asset| asset.amount)
+            .unwrap_or_default();
+        let spot_price = pool_spot_price(
+            deps.as_ref(),
+            adapter,
+            exchange,
+            AnsAsset::new(x.name.clone(), added_x),
+            &y.name,
+        )?;
+        // the spot prices of the deposits are weighted by their amounts of the first asset
+        if let Some(spot_price) = spot_price {
+            let total_x = x.amount + added_x;
+            entry.entry_price = Decimal::from_ratio(x.amount, total_x) * entry.entry_price
+                + Decimal::from_ratio(added_x, total_x) * spot_price;
+        }
+    }
+    for asset in assets {
+        if let Some(deposit) = entry.deposits.iter_mut().find(|d| d.name == asset.name) {
+            deposit.amount += asset.amount;
+        }
+    }
+    LP_ENTRIES.save(deps.storage, key, &entry)?;
+    Ok(())
+}
+
+/// Spot price of the deposited asset in the other asset of their pool before the deposit,
+/// `None` if the asset isn't deposited or the pool can't be simulated, like an empty pool
+fn pool_spot_price(
+    deps: Deps,
+    adapter: &DexAdapter,
+    exchange: &dyn DexCommand,
+    deposit: AnsAsset,
+    other: &AssetEntry,
+) -> DexResult<Option<Decimal>> {
+    if deposit.amount.is_zero() {
+        return Ok(None);
+    }
+    let ans = adapter.name_service(deps);
+    let pool = exchange.pair_address(deps, ans.host(), (deposit.name.clone(), other.clone()))?;
+    // the return of a swap at the spot price is the simulated return with its spread and commission
+    let simulation = exchange.simulate_swap(deps, pool, ans.query(&deposit)?, ans.query(other)?);
+    Ok(simulation
+        .ok()
+        .map(|(return_amount, spread, commission, _)| {
+            Decimal::from_ratio(return_amount + spread + commission, deposit.amount)
+        }))
+}
+
+/// LP tokens that depositing the assets mints at the current reserves of their pool,
+/// `None` if the dex doesn't expose the reserves or the pool is empty
+fn minted_lp_amount(
+    deps: Deps,
+    adapter: &DexAdapter,
+    exchange: &dyn DexCommand,
+    assets: &[AnsAsset],
+) -> DexResult<Option<Uint128>> {
+    let [x, y] = assets else {
+        return Ok(None);
+    };
+    let ans = adapter.name_service(deps);
+    let pool = exchange.pair_address(deps, ans.host(), (x.name.clone(), y.name.clone()))?;
+    let (reserves, total_share) = match exchange.pool_reserves(deps, pool) {
+        Err(DexError::NotImplemented(_)) => return Ok(None),
+        reserves => reserves?,
+    };
+    let reserve = |info: AssetInfo| {
+        reserves
+            .iter()
+            .find(|reserve| reserve.info == info)
+            .map(|reserve| reserve.amount)
+            .unwrap_or_default()
+    };
+    let reserve_x = reserve(ans.query(&x.name)?);
+    let reserve_y = reserve(ans.query(&y.name)?);
+    if total_share.is_zero() || reserve_x.is_zero() || reserve_y.is_zero() {
+        return Ok(None);
+    }
+    Ok(Some(std::cmp::min(
+        x.amount.multiply_ratio(total_share, reserve_x),
+        y.amount.multiply_ratio(total_share, reserve_y),
+    )))
+}
+
+/// Deposits of a symmetric provision, `None` if the dex doesn't expose the reserves that set the paired amount
+fn symmetric_deposits(
+    deps: Deps,
+    adapter: &DexAdapter,
+    exchange: &dyn DexCommand,
+    mut offer_asset: AnsAsset,
+    mut paired_assets: Vec<AssetEntry>,
+) -> DexResult<Option<Vec<AnsAsset>>> {
+    let [paired_asset] = paired_assets.as_mut_slice() else {
+        return Ok(None);
+    };
+    offer_asset.name.format();
+    paired_asset.format();
+    let ans = adapter.name_service(deps);
+    let pool = exchange.pair_address(
+        deps,
+        ans.host(),
+        (offer_asset.name.clone(), paired_asset.clone()),
+    )?;
+    let (reserves, _) = match exchange.pool_reserves(deps, pool) {
+        Err(DexError::NotImplemented(_)) => return Ok(None),
+        reserves => reserves?,
+    };
+    let reserve = |info: AssetInfo| {
+        reserves
+            .iter()
+            .find(|reserve| reserve.info == info)
+            .map(|reserve| reserve.amount)
+            .unwrap_or_default()
+    };
+    let offer_reserve = reserve(ans.query(&offer_asset.name)?);
+    if offer_reserve.is_zero() {
+        return Ok(None);
+    }
+    let paired_amount = offer_asset
+        .amount
+        .multiply_ratio(reserve(ans.query(&*paired_asset)?), offer_reserve);
+    let paired_asset = AnsAsset::new(paired_asset.clone(), paired_amount);
+    Ok(Some(vec![offer_asset, paired_asset]))
+}
+
+/// Reduce the deposits of the LP entry of the account in proportion to the LP tokens withdrawn
+fn record_lp_withdrawal(
+    deps: DepsMut,
+    adapter: &DexAdapter,
+    proxy: &Addr,
+    dex: &str,
+    mut lp_token: AssetEntry,
+    amount: Uint128,
+) -> DexResult<()> {
+    lp_token.format();
+    let key = (proxy, dex, &lp_token);
+    let Some(mut entry) = LP_ENTRIES.may_load(deps.storage, key)? else {
+        return Ok(());
+    };
+    // the withdrawal hasn't been executed yet
+    let balance = adapter
+        .name_service(deps.as_ref())
+        .query(&lp_token)?
+        .query_balance(&deps.querier, proxy)?;
+    if amount >= balance {
+        LP_ENTRIES.remove(deps.storage, key);
+        return Ok(());
+    }
+    for deposit in entry.deposits.iter_mut() {
+        deposit.amount -= deposit.amount.multiply_ratio(amount, balance);
+    }
+    entry.lp_amount -= entry.lp_amount.multiply_ratio(amount, balance);
+    LP_ENTRIES.save(deps.storage, key, &entry)?;
+    Ok(())
+}
+
+/// Messages of an action that is executed as part of other actions
+pub(crate) fn resolve_local_action_msgs(
+    deps: DepsMut,
+    env: &Env,
+    adapter: &DexAdapter,
+    exchange: &str,
+    action: DexAction,
+) -> DexResult<Vec<CosmosMsg>> {
+    let resolved = resolve_local_action(deps, env, adapter, exchange, action)?;
+    if resolved.forward.is_some() {
+        return Err(DexError::RecipientNotSupported(exchange.to_owned()));
+    }
+    if resolved.fee_asset.is_some() {
+        return Err(DexError::AskAssetFeeNotSupported(exchange.to_owned()));
+    }
+    Ok(resolved.msgs)
+}
+
+/// Handle a batch of actions on local exchanges.
+/// The actions are executed in a single proxy execution unless one of them takes the output of the previous action,
+/// in which case each action is executed in a sub-message and the output is measured in its reply.
+fn handle_batch(
+    mut deps: DepsMut,
+    env: Env,
+    adapter: DexAdapter,
+    actions: Vec<(DexName, DexAction)>,
+) -> DexResult {
+    let mut steps = local_batch_steps(env.clone(), actions)?;
+    let chained = steps.iter_mut().skip(1).any(|(_, action)| {
+        offered_amounts_mut(action)
+            .iter()
+            .any(|(_, amount)| amount.is_zero())
+    });
+
+    if !chained {
+        let mut msgs = vec![];
+        for (exchange, action) in steps {
+            msgs.extend(resolve_local_action_msgs(
+                deps.branch(),
+                &env,
+                &adapter,
+                &exchange,
+                action,
+            )?);
+        }
+        let proxy_msg = adapter
+            .executor(deps.as_ref())
+            .execute(msgs.into_iter().map(Into::into).collect())?;
+        return Ok(Response::new().add_message(proxy_msg));
+    }
+
+    BATCH.save(
+        deps.storage,
+        &BatchState {
+            account: adapter.account_base(deps.as_ref())?,
+            steps,
+            balances: vec![],
+        },
+    )?;
+    execute_batch_step(deps, &env, &adapter)
+}
+
+/// Verify that all the actions of a batch can be executed on the local chain
+pub(crate) fn local_batch_steps(
+    env: Env,
+    actions: Vec<(DexName, DexAction)>,
+) -> DexResult<Vec<(DexName, DexAction)>> {
+    if actions.is_empty() {
+        return Err(DexError::EmptyBatch {});
+    }
+    actions
+        .into_iter()
+        .map(|(dex_name, action)| {
+            let (local_dex_name, is_over_ibc) = is_over_ibc(env.clone(), &dex_name)?;
+            if is_over_ibc {
+                return Err(DexError::BatchOverIbc(dex_name));
+            }
+            Ok((local_dex_name, action))
+        })
+        .collect()
+}
+
+/// Execute the next step of the stored batch.
+/// The balances of the assets that the following step takes from this step are measured before it is executed.
+pub(crate) fn execute_batch_step(mut deps: DepsMut, env: &Env, adapter: &DexAdapter) -> DexResult {
+    let mut batch = BATCH.load(deps.storage)?;
+    let (exchange, action) = batch.steps.remove(0);
+    let msgs = resolve_local_action_msgs(deps.branch(), env, adapter, &exchange, action)?;
+    let actions = msgs.into_iter().map(Into::into).collect();
+
+    let Some((_, next_action)) = batch.steps.first_mut() else {
+        // last step
+        BATCH.remove(deps.storage);
+        let proxy_msg = adapter.executor(deps.as_ref()).execute(actions)?;
+        return Ok(Response::new().add_message(proxy_msg));
+    };
+
+    let ans = adapter.name_service(deps.as_ref());
+    batch.balances = offered_amounts_mut(next_action)
+        .into_iter()
+        .filter(|(_, amount)| amount.is_zero())
+        .map(|(asset, _)| {
+            let info = ans.query(asset)?;
+            let balance = info.query_balance(&deps.querier, &batch.account.proxy)?;
+            Ok((asset.clone(), info, balance))
+        })
+        .collect::<DexResult<_>>()?;
+    BATCH.save(deps.storage, &batch)?;
+
+    let sub_msg = adapter.executor(deps.as_ref()).execute_with_reply(
+        actions,
+        ReplyOn::Success,
+        BATCH_STEP_REPLY_ID,
+    )?;
+    Ok(Response::new().add_submessage(sub_msg))
+}
+
+/// Assets offered by the action, their amount is taken from the previous step of a batch when zero
+pub(crate) fn offered_amounts_mut(action: &mut DexAction) -> Vec<(&AssetEntry, &mut Uint128)> {
+    match action {
+        DexAction::ProvideLiquidity { assets, .. } => assets
+            .iter_mut()
+            .map(|AnsAsset { name, amount }| (&*name, amount))
+            .collect(),
+        DexAction::ProvideLiquiditySymmetric { offer_asset, .. }
+        | DexAction::Swap { offer_asset, .. } => {
+            vec![(&offer_asset.name, &mut offer_asset.amount)]
+        }
+        DexAction::WithdrawLiquidity {
+            lp_token, amount, ..
+        }
+        | DexAction::WithdrawLiquiditySingleAsset {
+            lp_token, amount, ..
+        } => vec![(&*lp_token, amount)],
+        DexAction::CustomSwap { offer_assets, .. } => offer_assets
+            .iter_mut()
+            .map(|AnsAsset { name, amount }| (&*name, amount))
+            .collect(),
+        DexAction::CreatePool {
+            initial_liquidity, ..
+        } => initial_liquidity
+            .iter_mut()
+            .flatten()
+            .map(|AnsAsset { name, amount }| (&*name, amount))
+            .collect(),
+        // raw assets have no ANS entry to match the output of the previous step
+        DexAction::RawSwap { .. }
+        | DexAction::RawProvideLiquidity { .. }
+        | DexAction::RawWithdrawLiquidity { .. } => vec![],
+    }
+}
+
+/// Add a swap to the statistics of the account and of the traded pair.
+/// Fees charged on the ask asset aren't known before the swap and are not included.
+fn record_swap(
+    storage: &mut dyn Storage,
+    proxy: &Addr,
+    dex: &str,
+    mut offer_asset: AnsAsset,
+    mut ask_asset: AssetEntry,
+    fee_mode: FeeMode,
+) -> DexResult<()> {
+    offer_asset.name.format();
+    ask_asset.format();
+    let fee = match fee_mode {
+        FeeMode::OfferAsset => SWAP_FEE.load(storage)?.compute(offer_asset.amount),
+        FeeMode::AskAsset => Uint128::zero(),
+    };
+    let add_swap = |stats: Option<TradeStats>| -> StdResult<TradeStats> {
+        let mut stats = stats.unwrap_or_default();
+        stats.volume += offer_asset.amount;
+        stats.fees += fee;
+        stats.swaps += 1;
+        Ok(stats)
+    };
+
+    ACCOUNT_STATS.update(storage, (proxy, dex, &offer_asset.name), add_swap)?;
+    let pairing = DexAssetPairing::new(offer_asset.name.clone(), ask_asset, dex);
+    pair_stats().update(storage, &pairing, add_swap)?;
+    Ok(())
 }
 
 /// Handle an adapter request that can be executed on an IBC chain
+#[allow(clippy::too_many_arguments)]
 fn handle_ibc_request(
-    deps: &DepsMut,
+    mut deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     adapter: &DexAdapter,
     dex_name: DexName,
-    action: &DexAction,
+    action: DexAction,
+    return_proceeds: bool,
+    deadline: Option<Expiration>,
 ) -> DexResult {
     let host_chain = dex_name;
     let ans = adapter.name_service(deps.as_ref());
-    let ibc_client = adapter.ibc_client(deps.as_ref());
-    // get the to-be-sent assets from the action
-    let coins = resolve_assets_to_transfer(deps.as_ref(), action, ans.host())?;
-    // construct the ics20 call(s)
-    let ics20_transfer_msg = ibc_client.ics20_transfer(host_chain.clone(), coins)?;
-    // construct the action to be called on the host
-    let action = abstract_sdk::core::ibc_host::HostAction::App {
-        msg: to_binary(&action)?,
-    };
-    let maybe_contract_info = deps.querier.query_wasm_contract_info(info.sender.clone());
-    let callback = if maybe_contract_info.is_err() {
-        None
+    let account = adapter.account_base(deps.as_ref())?;
+    // the host doesn't know the settings of the account so they are enforced before the action is sent
+    crate::adapter::DexAdapter::assert_assets_allowed(
+        adapter,
+        deps.as_ref(),
+        &account.proxy,
+        &action,
+        &host_chain,
+    )?;
+    if has_price_guard(deps.as_ref(), &account.proxy) {
+        if let DexAction::Swap { .. } | DexAction::CustomSwap { .. } = action {
+            return Err(DexError::PriceGuardOverIbc(host_chain));
+        }
+    }
+    spend_offered_assets(deps.branch(), &env, adapter, &account.proxy, &action)?;
+
+    let action_id = IBC_ACTION_SEQUENCE
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        + 1;
+    IBC_ACTION_SEQUENCE.save(deps.storage, &action_id)?;
+
+    // the result is reported back to this adapter, which forwards it to the requesting contract
+    let callback = CallbackInfo {
+        id: ibc_callback_id(IBC_DEX_ID, action_id),
+        receiver: env.contract.address.to_string(),
+    };
+    let request_msg = ibc_request_msg(
+        deps.as_ref(),
+        ans.host(),
+        &account.proxy,
+        host_chain.clone(),
+        &action,
+        deadline,
+        Some(callback),
+    )?;
+    let proxy_msg = if return_proceeds {
+        // the action is sent once the balances of the remote account before it are received,
+        // so that only what the action adds to them is returned
+        balances_request_msg(
+            &account.proxy,
+            host_chain.clone(),
+            ibc_callback_id(IBC_DEX_BALANCE_ID, action_id),
+            &env.contract.address,
+        )?
     } else {
-        Some(CallbackInfo {
-            id: IBC_DEX_ID.to_string(),
-            receiver: info.sender.into_string(),
-        })
+        request_msg
     };
-    let ibc_action_msg = ibc_client.host_action(host_chain, action, callback, ACTION_RETRIES)?;
 
-    // call both messages on the proxy
-    Ok(Response::new().add_messages(vec![ics20_transfer_msg, ibc_action_msg]))
+    // keep track of the action until its callback is received
+    IBC_ACTIONS.save(
+        deps.storage,
+        (&account.proxy, action_id),
+        &IbcActionInfo {
+            dex: host_chain,
+            action,
+            status: IbcActionStatus::Pending,
+        },
+    )?;
+    let callback_receiver = deps
+        .querier
+        .query_wasm_contract_info(info.sender.clone())
+        .is_ok()
+        .then_some(info.sender);
+    PENDING_IBC_ACTIONS.save(
+        deps.storage,
+        action_id,
+        &PendingIbcAction {
+            account,
+            callback_receiver,
+            return_proceeds,
+            balances_before: vec![],
+            deadline,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_message(proxy_msg)
+        .add_attribute("ibc_action_id", action_id.to_string()))
+}
+
+/// Ask the host chain of a remote dex to simulate a swap
+fn request_remote_quote(
+    deps: DepsMut,
+    env: Env,
+    adapter: &DexAdapter,
+    dex_name: DexName,
+    mut offer_asset: OfferAsset,
+    mut ask_asset: AssetEntry,
+) -> DexResult {
+    let (local_dex_name, is_over_ibc) = is_over_ibc(env.clone(), &dex_name)?;
+    if !is_over_ibc {
+        return Err(DexError::LocalDexQuote(dex_name));
+    }
+    offer_asset.name.format();
+    ask_asset.format();
+    let host_chain = local_dex_name;
+    let account = adapter.account_base(deps.as_ref())?;
+    // the dex adapter of the host chain simulates the swap
+    let remote_adapter = adapter.name_service(deps.as_ref()).query(&ContractEntry {
+        protocol: host_chain.clone(),
+        contract: REMOTE_DEX_ADAPTER.to_string(),
+    })?;
+    let quote_id = REMOTE_QUOTE_SEQUENCE
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        + 1;
+    REMOTE_QUOTE_SEQUENCE.save(deps.storage, &quote_id)?;
+
+    // the host answers with the SimulateSwapResponse of the query
+    let simulation = QueryMsg::Module(DexQueryMsg::SimulateSwap {
+        offer_asset: offer_asset.clone(),
+        ask_asset: ask_asset.clone(),
+        dex: Some(host_chain.clone()),
+        fee_mode: FeeMode::default(),
+    });
+    let ibc_msg = IbcClientMsg::SendPacket {
+        host_chain,
+        action: HostAction::Query {
+            msgs: vec![wasm_smart_query(remote_adapter, &simulation)?],
+        },
+        callback_info: Some(CallbackInfo {
+            id: ibc_callback_id(IBC_DEX_QUOTE_ID, quote_id),
+            receiver: env.contract.address.into_string(),
+        }),
+        retries: ACTION_RETRIES,
+    };
+    let proxy_msg = wasm_execute(
+        &account.proxy,
+        &ProxyExecuteMsg::IbcAction {
+            msgs: vec![ibc_msg],
+        },
+        vec![],
+    )?;
+    PENDING_REMOTE_QUOTES.save(
+        deps.storage,
+        quote_id,
+        &PendingQuote {
+            account,
+            dex: dex_name,
+            offer_asset,
+            ask_asset,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_message(proxy_msg)
+        .add_attribute("quote_id", quote_id.to_string()))
+}
+
+/// Construct the proxy message that sends the action, and the assets it requires, to the host chain
+pub(crate) fn ibc_request_msg(
+    deps: Deps,
+    ans_host: &AnsHost,
+    proxy: &Addr,
+    host_chain: String,
+    action: &DexAction,
+    deadline: Option<Expiration>,
+    callback: Option<CallbackInfo>,
+) -> DexResult<CosmosMsg> {
+    assert_no_account_recipient(action)?;
+    // get the to-be-sent assets from the action
+    let coins = resolve_assets_to_transfer(deps, action, ans_host)?;
+    let ibc_msgs = vec![
+        // the ics20 transfer
+        IbcClientMsg::SendFunds {
+            host_chain: host_chain.clone(),
+            funds: coins,
+        },
+        // the action to be called on the host
+        IbcClientMsg::SendPacket {
+            host_chain: host_chain.clone(),
+            action: HostAction::App {
+                msg: to_binary(&HostDexAction {
+                    action: action.clone(),
+                    deadline,
+                })?,
+            },
+            callback_info: callback,
+            retries: ACTION_RETRIES,
+        },
+    ];
+    // the ibc client only accepts messages from the proxy
+    let proxy_msg = wasm_execute(
+        proxy,
+        &ProxyExecuteMsg::IbcAction { msgs: ibc_msgs },
+        vec![],
+    )?;
+    Ok(proxy_msg.into())
+}
+
+/// Account ids would be resolved against the account registry of the host chain, where they can be another account
+fn assert_no_account_recipient(action: &DexAction) -> DexResult<()> {
+    let recipient = match action {
+        DexAction::ProvideLiquidity { recipient, .. }
+        | DexAction::WithdrawLiquidity { recipient, .. }
+        | DexAction::WithdrawLiquiditySingleAsset { recipient, .. }
+        | DexAction::Swap { recipient, .. } => recipient,
+        _ => return Ok(()),
+    };
+    match recipient {
+        Some(Recipient::Account(account_id)) => Err(DexError::AccountRecipientOverIbc(*account_id)),
+        _ => Ok(()),
+    }
 }
 
 pub(crate) fn resolve_assets_to_transfer(
@@ -133,10 +1175,31 @@ pub(crate) fn resolve_assets_to_transfer(
             let coins: Result<Vec<Coin>, _> = assets.iter().map(offer_to_coin).collect();
             coins
         }
-        DexAction::ProvideLiquiditySymmetric { .. } => Err(DexError::Std(StdError::generic_err(
-            "Cross-chain symmetric provide liquidity not supported.",
-        ))),
-        DexAction::WithdrawLiquidity { lp_token, amount } => Ok(vec![offer_to_coin(&AnsAsset {
+        DexAction::ProvideLiquiditySymmetric {
+            offer_asset,
+            paired_assets,
+            max_paired_assets,
+        } => {
+            // the paired amounts are only known on the remote chain so transfer the provided maximum
+            let max_paired_assets = max_paired_assets.as_deref().unwrap_or_default();
+            if let Some(missing) = paired_assets
+                .iter()
+                .find(|paired| !max_paired_assets.iter().any(|max| &max.name == *paired))
+            {
+                return Err(DexError::MissingPairedAssetBound(missing.to_string()));
+            }
+            let coins: Result<Vec<Coin>, _> = std::iter::once(offer_asset)
+                .chain(max_paired_assets)
+                .map(offer_to_coin)
+                .collect();
+            coins
+        }
+        DexAction::WithdrawLiquidity {
+            lp_token, amount, ..
+        }
+        | DexAction::WithdrawLiquiditySingleAsset {
+            lp_token, amount, ..
+        } => Ok(vec![offer_to_coin(&AnsAsset {
             name: lp_token.to_owned(),
             amount: amount.to_owned(),
         })?]),
@@ -145,6 +1208,20 @@ pub(crate) fn resolve_assets_to_transfer(
             let coins: Result<Vec<Coin>, _> = offer_assets.iter().map(offer_to_coin).collect();
             coins
         }
+        DexAction::CreatePool {
+            initial_liquidity, ..
+        } => {
+            let coins: Result<Vec<Coin>, _> = initial_liquidity
+                .iter()
+                .flatten()
+                .map(offer_to_coin)
+                .collect();
+            coins
+        }
+        // raw assets are only known on the local chain
+        DexAction::RawSwap { .. }
+        | DexAction::RawProvideLiquidity { .. }
+        | DexAction::RawWithdrawLiquidity { .. } => Err(DexError::RawActionOverIbc {}),
     }
     .map_err(Into::into)
 }
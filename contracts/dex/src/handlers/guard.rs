@@ -0,0 +1,521 @@
+use crate::contract::{DexAdapter, DexResult};
+use crate::msg::{
+    DexAction, FeeMode, OracleConfig, OraclePriceResponse, OracleQueryMsg, PriceSource,
+};
+use crate::state::{
+    PriceObservation, SpendState, MAX_PRICE_IMPACT, ORACLE_CONFIG, PRICE_HISTORY, SPEND_LIMITS,
+    SWAP_FEE, TWAP_PAIRS,
+};
+use abstract_core::objects::{AnsAsset, AssetEntry, DexAssetPairing};
+use abstract_dex_adapter_traits::{DexCommand, DexError};
+use abstract_sdk::features::AbstractNameService;
+use cosmwasm_std::{
+    Addr, Decimal, Decimal256, Deps, DepsMut, Env, Order, StdError, StdResult, Storage, Uint128,
+};
+use cw_asset::{Asset, AssetInfo};
+use cw_storage_plus::Bound;
+
+/// Longest window of a time-weighted average price source, in seconds
+pub(crate) const MAX_TWAP_WINDOW: u64 = 7 * 24 * 60 * 60;
+/// Observations removed from the price history of a pairing per recorded observation
+const MAX_PRUNED_OBSERVATIONS: usize = 10;
+
+/// Simulated outcome of a swap action, before it's executed
+pub(crate) struct SwapSimulation {
+    /// Only known for swaps between assets registered in the ANS
+    pub pairing: Option<DexAssetPairing>,
+    /// Offered amount that reaches the pool, after the usage fee when it's charged on the offer asset
+    pub offer_amount: Uint128,
+    pub return_amount: Uint128,
+    pub spread_amount: Uint128,
+    /// Assets of the swap, matched with the price sources of the account when the pairing isn't known
+    pub offer_info: AssetInfo,
+    pub ask_info: AssetInfo,
+}
+
+impl SwapSimulation {
+    /// Amount of ask asset per offer asset at the spot price of the pool
+    fn spot_price(&self) -> Option<Decimal> {
+        let spot_return = self.return_amount + self.spread_amount;
+        (!self.offer_amount.is_zero() && !spot_return.is_zero())
+            .then(|| Decimal::from_ratio(spot_return, self.offer_amount))
+    }
+}
+
+/// Simulate the swap of a swap action, `None` for other actions
+fn simulate_swap_action(
+    deps: Deps,
+    adapter: &DexAdapter,
+    exchange: &dyn DexCommand,
+    action: &DexAction,
+) -> DexResult<Option<SwapSimulation>> {
+    let (pairing, pool, offer_asset, ask_asset, fee_mode) = match action {
+        DexAction::Swap {
+            offer_asset,
+            ask_asset,
+            fee_mode,
+            ..
+        } => {
+            let mut offer_asset = offer_asset.clone();
+            let mut ask_asset = ask_asset.clone();
+            offer_asset.name.format();
+            ask_asset.format();
+            let ans = adapter.name_service(deps);
+            let pool = exchange.pair_address(
+                deps,
+                ans.host(),
+                (offer_asset.name.clone(), ask_asset.clone()),
+            )?;
+            let pairing =
+                DexAssetPairing::new(offer_asset.name.clone(), ask_asset.clone(), exchange.name());
+            (
+                Some(pairing),
+                pool,
+                ans.query(&offer_asset)?,
+                ans.query(&ask_asset)?,
+                fee_mode.clone(),
+            )
+        }
+        DexAction::RawSwap {
+            pool,
+            offer_asset,
+            ask_asset,
+            ..
+        } => (
+            None,
+            pool.check(deps.api)?,
+            offer_asset.check(deps.api, None)?,
+            ask_asset.check(deps.api, None)?,
+            FeeMode::OfferAsset,
+        ),
+        _ => return Ok(None),
+    };
+
+    // the usage fee is deducted before the swap unless it's charged on the ask asset
+    let offer_amount = match fee_mode {
+        FeeMode::OfferAsset => {
+            let fee = SWAP_FEE.load(deps.storage)?;
+            offer_asset.amount - fee.compute(offer_asset.amount)
+        }
+        FeeMode::AskAsset => offer_asset.amount,
+    };
+    let (return_amount, spread_amount, _, _) = exchange.simulate_swap(
+        deps,
+        pool,
+        Asset::new(offer_asset.info.clone(), offer_amount),
+        ask_asset.clone(),
+    )?;
+    Ok(Some(SwapSimulation {
+        pairing,
+        offer_amount,
+        return_amount,
+        spread_amount,
+        offer_info: offer_asset.info,
+        ask_info: ask_asset,
+    }))
+}
+
+/// Simulate the swap of a swap action when the account guards the price of its swaps, `None` otherwise.
+/// Custom swaps can't be simulated so they are rejected by accounts with a price guard.
+pub(crate) fn guarded_swap_simulation(
+    deps: Deps,
+    adapter: &DexAdapter,
+    exchange: &dyn DexCommand,
+    proxy: &Addr,
+    action: &DexAction,
+) -> DexResult<Option<SwapSimulation>> {
+    if has_price_guard(deps, proxy) {
+        if let DexAction::CustomSwap { .. } = action {
+            return Err(DexError::CustomSwapPriceGuard {});
+        }
+    } else if !is_tracked_swap(deps, exchange, action) {
+        return Ok(None);
+    }
+    simulate_swap_action(deps, adapter, exchange, action)
+}
+
+/// Whether the account checks the price of its swaps
+pub(crate) fn has_price_guard(deps: Deps, proxy: &Addr) -> bool {
+    MAX_PRICE_IMPACT.has(deps.storage, proxy) || ORACLE_CONFIG.has(deps.storage, proxy)
+}
+
+/// Whether the action swaps a pair whose spot price is recorded for a time-weighted average price source
+fn is_tracked_swap(deps: Deps, exchange: &dyn DexCommand, action: &DexAction) -> bool {
+    let DexAction::Swap {
+        offer_asset,
+        ask_asset,
+        ..
+    } = action
+    else {
+        return false;
+    };
+    let mut offer_asset = offer_asset.name.clone();
+    let mut ask_asset = ask_asset.clone();
+    offer_asset.format();
+    ask_asset.format();
+    let pairing = DexAssetPairing::new(offer_asset.clone(), ask_asset.clone(), exchange.name());
+    let inverse = DexAssetPairing::new(ask_asset, offer_asset, exchange.name());
+    TWAP_PAIRS.has(deps.storage, &pairing) || TWAP_PAIRS.has(deps.storage, &inverse)
+}
+
+/// Pairings that the time-weighted average price sources of the config are computed from
+fn twap_pairings(config: &OracleConfig) -> Vec<DexAssetPairing> {
+    config
+        .sources
+        .iter()
+        .filter_map(|(asset, source)| match source {
+            PriceSource::Twap { dex, quote, .. } if quote != asset => {
+                Some(DexAssetPairing::new(asset.clone(), quote.clone(), dex))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Reject time-weighted average price sources whose window exceeds the recorded history
+pub(crate) fn assert_twap_windows(config: &OracleConfig) -> DexResult<()> {
+    for (_, source) in &config.sources {
+        if let PriceSource::Twap { window, .. } = source {
+            if *window > MAX_TWAP_WINDOW {
+                return Err(DexError::InvalidTwapWindow(*window, MAX_TWAP_WINDOW));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Count the pairings of the time-weighted average price sources of an account that changes its oracle config
+pub(crate) fn update_twap_pairs(
+    storage: &mut dyn Storage,
+    old_config: Option<&OracleConfig>,
+    new_config: Option<&OracleConfig>,
+) -> DexResult<()> {
+    for pairing in old_config.map(twap_pairings).unwrap_or_default() {
+        match TWAP_PAIRS.may_load(storage, &pairing)? {
+            Some(count) if count > 1 => TWAP_PAIRS.save(storage, &pairing, &(count - 1))?,
+            _ => TWAP_PAIRS.remove(storage, &pairing),
+        }
+    }
+    for pairing in new_config.map(twap_pairings).unwrap_or_default() {
+        let count = TWAP_PAIRS.may_load(storage, &pairing)?.unwrap_or_default();
+        TWAP_PAIRS.save(storage, &pairing, &(count + 1))?;
+    }
+    Ok(())
+}
+
+/// Reject swaps whose price impact exceeds the maximum set by the account
+pub(crate) fn assert_price_impact(
+    deps: Deps,
+    proxy: &Addr,
+    simulation: &SwapSimulation,
+) -> DexResult<()> {
+    let Some(max_price_impact) = MAX_PRICE_IMPACT.may_load(deps.storage, proxy)? else {
+        return Ok(());
+    };
+    let spot_return = simulation.return_amount + simulation.spread_amount;
+    if spot_return.is_zero() {
+        return Ok(());
+    }
+    let price_impact = Decimal::from_ratio(simulation.spread_amount, spot_return);
+    if price_impact > max_price_impact {
+        return Err(DexError::MaxPriceImpactExceeded(
+            price_impact,
+            max_price_impact,
+        ));
+    }
+    Ok(())
+}
+
+/// Reject swaps whose price deviates from the price of the oracles of the account by more than its maximum.
+/// Swaps of assets without a price source are not checked, the assets of raw swaps are matched with the resolved sources.
+pub(crate) fn assert_oracle_deviation(
+    deps: Deps,
+    env: &Env,
+    adapter: &DexAdapter,
+    proxy: &Addr,
+    simulation: &SwapSimulation,
+) -> DexResult<()> {
+    let Some(OracleConfig {
+        sources,
+        max_deviation,
+    }) = ORACLE_CONFIG.may_load(deps.storage, proxy)?
+    else {
+        return Ok(());
+    };
+    let (offer_asset, ask_asset) = match &simulation.pairing {
+        Some(pairing) => (pairing.asset_x().clone(), pairing.asset_y().clone()),
+        // raw swaps are checked with the sources of the assets that resolve to their assets
+        None => {
+            let ans = adapter.name_service(deps);
+            let entry = |info: &AssetInfo| {
+                sources
+                    .iter()
+                    .map(|(entry, _)| entry)
+                    .find(|entry| ans.query(*entry).is_ok_and(|resolved| &resolved == info))
+                    .cloned()
+            };
+            let (Some(offer_asset), Some(ask_asset)) =
+                (entry(&simulation.offer_info), entry(&simulation.ask_info))
+            else {
+                return Ok(());
+            };
+            (offer_asset, ask_asset)
+        }
+    };
+    let source = |asset: &AssetEntry| {
+        sources
+            .iter()
+            .find(|(entry, _)| entry == asset)
+            .map(|(_, source)| source)
+    };
+    let (Some(offer_source), Some(ask_source)) = (source(&offer_asset), source(&ask_asset)) else {
+        return Ok(());
+    };
+
+    let offer_price = source_price(deps, env, adapter, &offer_asset, offer_source)?;
+    let ask_price = source_price(deps, env, adapter, &ask_asset, ask_source)?;
+    if simulation.offer_amount.is_zero() || ask_price.is_zero() || offer_price.is_zero() {
+        return Ok(());
+    }
+    let oracle_price = offer_price / ask_price;
+    let price = Decimal::from_ratio(simulation.return_amount, simulation.offer_amount);
+    if price.abs_diff(oracle_price) / oracle_price > max_deviation {
+        return Err(DexError::OracleDeviationExceeded(price, oracle_price));
+    }
+    Ok(())
+}
+
+/// Amounts of the limited assets offered by a swap or liquidity provision.
+/// Only the offered asset of a symmetric provision is counted, its paired amounts are set by the pool.
+pub(crate) fn limited_spends(
+    deps: Deps,
+    adapter: &DexAdapter,
+    proxy: &Addr,
+    action: &DexAction,
+) -> DexResult<Vec<(AssetEntry, SpendState, Uint128)>> {
+    let (entries, raw_assets) = match action {
+        DexAction::Swap { offer_asset, .. }
+        | DexAction::ProvideLiquiditySymmetric { offer_asset, .. } => {
+            (vec![offer_asset.clone()], vec![])
+        }
+        DexAction::ProvideLiquidity { assets, .. }
+        | DexAction::CustomSwap {
+            offer_assets: assets,
+            ..
+        } => (assets.clone(), vec![]),
+        DexAction::CreatePool {
+            initial_liquidity, ..
+        } => (initial_liquidity.clone().unwrap_or_default(), vec![]),
+        DexAction::RawSwap { offer_asset, .. } => (vec![], vec![offer_asset.clone()]),
+        DexAction::RawProvideLiquidity { assets, .. } => (vec![], assets.clone()),
+        DexAction::WithdrawLiquidity { .. }
+        | DexAction::WithdrawLiquiditySingleAsset { .. }
+        | DexAction::RawWithdrawLiquidity { .. } => return Ok(vec![]),
+    };
+
+    let mut offered: Vec<(AssetEntry, Uint128)> = entries
+        .into_iter()
+        .map(|AnsAsset { mut name, amount }| {
+            name.format();
+            (name, amount)
+        })
+        .collect();
+    if !raw_assets.is_empty() {
+        // raw assets are matched with the assets that the limited entries resolve to,
+        // entries that aren't registered in the ANS can't match any asset
+        let ans = adapter.name_service(deps);
+        let limited = SPEND_LIMITS
+            .prefix(proxy)
+            .keys(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?
+            .into_iter()
+            .filter_map(|entry| Some((ans.query(&entry).ok()?, entry)))
+            .collect::<Vec<_>>();
+        for asset in raw_assets {
+            let asset = asset.check(deps.api, None)?;
+            for (info, entry) in &limited {
+                if *info == asset.info {
+                    offered.push((entry.clone(), asset.amount));
+                }
+            }
+        }
+    }
+
+    let mut spends = vec![];
+    for (asset, amount) in offered {
+        if let Some(state) = SPEND_LIMITS.may_load(deps.storage, (proxy, &asset))? {
+            spends.push((asset, state, amount));
+        }
+    }
+    Ok(spends)
+}
+
+/// Reject spends that exceed the remaining allowance of their limit
+pub(crate) fn assert_spend_limits(
+    env: &Env,
+    spends: &[(AssetEntry, SpendState, Uint128)],
+) -> DexResult<()> {
+    let now = env.block.time.seconds();
+    for (asset, state, amount) in spends {
+        let remaining = state.remaining_at(now);
+        if *amount > remaining {
+            return Err(DexError::SpendLimitExceeded(asset.to_string(), remaining));
+        }
+    }
+    Ok(())
+}
+
+/// Count the assets offered by a swap or liquidity provision against the spend limits of the account
+pub(crate) fn spend_offered_assets(
+    deps: DepsMut,
+    env: &Env,
+    adapter: &DexAdapter,
+    proxy: &Addr,
+    action: &DexAction,
+) -> DexResult<()> {
+    let spends = limited_spends(deps.as_ref(), adapter, proxy, action)?;
+    assert_spend_limits(env, &spends)?;
+
+    let now = env.block.time.seconds();
+    for (asset, mut state, amount) in spends {
+        if state.resets_at(now).is_none() {
+            state.window_start = state.window_start_at(now);
+            state.spent = Uint128::zero();
+        }
+        state.spent += amount;
+        SPEND_LIMITS.save(deps.storage, (proxy, &asset), &state)?;
+    }
+    Ok(())
+}
+
+/// Price of the asset according to its source
+fn source_price(
+    deps: Deps,
+    env: &Env,
+    adapter: &DexAdapter,
+    asset: &AssetEntry,
+    source: &PriceSource,
+) -> DexResult<Decimal> {
+    match source {
+        PriceSource::Oracle { contract } => {
+            let oracle = adapter.name_service(deps).query(contract)?;
+            let response: OraclePriceResponse = deps.querier.query_wasm_smart(
+                oracle,
+                &OracleQueryMsg::Price {
+                    asset: asset.clone(),
+                },
+            )?;
+            Ok(response.price)
+        }
+        PriceSource::Twap { quote, .. } if quote == asset => Ok(Decimal::one()),
+        PriceSource::Twap { dex, quote, window } => twap(
+            deps.storage,
+            env,
+            &DexAssetPairing::new(asset.clone(), quote.clone(), dex),
+            *window,
+        ),
+    }
+}
+
+/// Record the spot price of the pool of a swap for the time-weighted average price sources that use its pair
+pub(crate) fn record_spot_price(
+    storage: &mut dyn Storage,
+    env: &Env,
+    simulation: &SwapSimulation,
+) -> DexResult<()> {
+    let (Some(pairing), Some(price)) = (&simulation.pairing, simulation.spot_price()) else {
+        return Ok(());
+    };
+    let (offer_asset, ask_asset) = (pairing.asset_x(), pairing.asset_y());
+    let inverse = DexAssetPairing::new(ask_asset.clone(), offer_asset.clone(), pairing.dex());
+    let inverse_price = Decimal::one() / price;
+
+    let now = env.block.time.seconds();
+    for (pairing, price) in [(pairing, price), (&inverse, inverse_price)] {
+        if !TWAP_PAIRS.has(storage, pairing) {
+            continue;
+        }
+        prune_price_history(storage, pairing, now.saturating_sub(MAX_TWAP_WINDOW))?;
+        let cumulative = match latest_observation(storage, pairing, now)? {
+            Some(latest) => latest.cumulative_at(now),
+            None => Decimal256::zero(),
+        };
+        PRICE_HISTORY.save(
+            storage,
+            (pairing, now),
+            &PriceObservation {
+                time: now,
+                price,
+                cumulative,
+            },
+        )?;
+    }
+    Ok(())
+}
+
+/// Remove observations of the pairing that no window starts after, at most [`MAX_PRUNED_OBSERVATIONS`] at a time.
+/// The last observation before the cutoff is kept as it's the price at the start of the longest window.
+fn prune_price_history(
+    storage: &mut dyn Storage,
+    pairing: &DexAssetPairing,
+    cutoff: u64,
+) -> DexResult<()> {
+    let Some(kept) = latest_observation(storage, pairing, cutoff)? else {
+        return Ok(());
+    };
+    let pruned = PRICE_HISTORY
+        .prefix(pairing)
+        .keys(
+            storage,
+            None,
+            Some(Bound::exclusive(kept.time)),
+            Order::Ascending,
+        )
+        .take(MAX_PRUNED_OBSERVATIONS)
+        .collect::<StdResult<Vec<_>>>()?;
+    for time in pruned {
+        PRICE_HISTORY.remove(storage, (pairing, time));
+    }
+    Ok(())
+}
+
+/// Time-weighted average price of the pairing over the window, in seconds, that ends at the current block
+pub(crate) fn twap(
+    storage: &dyn Storage,
+    env: &Env,
+    pairing: &DexAssetPairing,
+    window: u64,
+) -> DexResult<Decimal> {
+    let now = env.block.time.seconds();
+    let unavailable = || DexError::TwapUnavailable(pairing.to_string());
+    let latest = latest_observation(storage, pairing, now)?.ok_or_else(unavailable)?;
+    if window == 0 {
+        return Ok(latest.price);
+    }
+    let start = now.checked_sub(window).ok_or_else(unavailable)?;
+    let first = latest_observation(storage, pairing, start)?.ok_or_else(unavailable)?;
+
+    let average = (latest.cumulative_at(now) - first.cumulative_at(start))
+        / Decimal256::from_ratio(window, 1u64);
+    Decimal::try_from(average).map_err(|e| StdError::generic_err(e.to_string()).into())
+}
+
+/// Last observation of the pairing at or before the time
+fn latest_observation(
+    storage: &dyn Storage,
+    pairing: &DexAssetPairing,
+    time: u64,
+) -> DexResult<Option<PriceObservation>> {
+    PRICE_HISTORY
+        .prefix(pairing)
+        .range(
+            storage,
+            None,
+            Some(Bound::inclusive(time)),
+            Order::Descending,
+        )
+        .next()
+        .transpose()
+        .map(|observation| observation.map(|(_, observation)| observation))
+        .map_err(Into::into)
+}
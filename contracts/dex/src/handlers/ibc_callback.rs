@@ -0,0 +1,490 @@
+use crate::contract::{DexAdapter, DexResult, IBC_CALLBACK_FORWARD_REPLY_ID};
+use crate::handlers::execute::{ibc_request_msg, ACTION_RETRIES};
+use crate::msg::{
+    DexAction, IbcActionStatus, RemoteQuote, SimulateSwapResponse, IBC_DEX_BALANCE_ID, IBC_DEX_ID,
+    IBC_DEX_QUOTE_ID, IBC_DEX_RETURN_ID,
+};
+use crate::state::{
+    PendingQuote, PendingReturn, IBC_ACTIONS, PENDING_IBC_ACTIONS, PENDING_REMOTE_QUOTES,
+    PENDING_RETURNS, REMOTE_QUOTES,
+};
+use abstract_core::abstract_ica::{BalancesResponse, IbcQueryResponse, IbcResponseMsg, StdAck};
+use abstract_core::ibc_client::{CallbackInfo, ExecuteMsg as IbcClientMsg};
+use abstract_core::ibc_host::HostAction;
+use abstract_core::objects::{
+    AccountBase, AnsEntryConvertor, AssetEntry, ChannelEntry, DexAssetPairing, LpToken,
+};
+use abstract_core::proxy::ExecuteMsg as ProxyExecuteMsg;
+use abstract_core::{IBC_CLIENT, ICS20};
+use abstract_dex_adapter_traits::DexError;
+use abstract_sdk::features::AbstractNameService;
+use abstract_sdk::{ModuleInterface, Resolve};
+use cosmwasm_std::{
+    from_binary, wasm_execute, Addr, ChannelResponse, Coin, CosmosMsg, DepsMut, Env, IbcMsg,
+    IbcQuery, IbcTimeout, MessageInfo, Reply, Response, SubMsg,
+};
+use cw_asset::AssetInfo;
+use sha2::{Digest, Sha256};
+
+/// Seconds before the transfer of returned proceeds times out
+const RETURN_TIMEOUT: u64 = 60 * 60;
+
+/// Callback id of a request, "<callback prefix>:<request id>"
+pub(crate) fn ibc_callback_id(prefix: u32, request_id: u64) -> String {
+    format!("{prefix}:{request_id}")
+}
+
+/// Callback prefix and request id of a callback of an IBC request sent by the adapter
+fn parse_callback_id(id: &str) -> Option<(u32, u64)> {
+    let (prefix, request_id) = id.split_once(':')?;
+    let prefix = prefix.parse().ok()?;
+    if ![
+        IBC_DEX_ID,
+        IBC_DEX_QUOTE_ID,
+        IBC_DEX_RETURN_ID,
+        IBC_DEX_BALANCE_ID,
+    ]
+    .contains(&prefix)
+    {
+        return None;
+    }
+    Some((prefix, request_id.parse().ok()?))
+}
+
+/// Whether the callback belongs to an IBC request sent by the adapter
+pub fn is_request_callback(id: &str) -> bool {
+    parse_callback_id(id).is_some()
+}
+
+/// Request the balances of the remote account of the proxy, reported to the adapter under the callback id
+pub(crate) fn balances_request_msg(
+    proxy: &Addr,
+    host_chain: String,
+    callback_id: String,
+    receiver: &Addr,
+) -> DexResult<CosmosMsg> {
+    let ibc_msg = IbcClientMsg::SendPacket {
+        host_chain,
+        action: HostAction::Balances {},
+        callback_info: Some(CallbackInfo {
+            id: callback_id,
+            receiver: receiver.to_string(),
+        }),
+        retries: ACTION_RETRIES,
+    };
+    Ok(wasm_execute(
+        proxy,
+        &ProxyExecuteMsg::IbcAction {
+            msgs: vec![ibc_msg],
+        },
+        vec![],
+    )?
+    .into())
+}
+
+/// Forward the callback of an IBC action to the contract that requested it.
+/// The requester receives the callback it received before the adapter tracked the actions,
+/// a failing receiver doesn't revert the status of the action.
+fn forward_callback(receiver: Addr, ack: StdAck) -> DexResult<SubMsg> {
+    let callback = IbcResponseMsg {
+        id: IBC_DEX_ID.to_string(),
+        msg: ack,
+    }
+    .into_cosmos_msg(receiver)?;
+    Ok(SubMsg::reply_on_error(
+        callback,
+        IBC_CALLBACK_FORWARD_REPLY_ID,
+    ))
+}
+
+/// Only the ibc client of the account that sent the action reports its result
+fn assert_ibc_client(
+    deps: &DepsMut,
+    info: &MessageInfo,
+    adapter: &mut DexAdapter,
+    account: &AccountBase,
+    id: &str,
+) -> DexResult<()> {
+    adapter.target_account = Some(account.clone());
+    let ibc_client = adapter.modules(deps.as_ref()).module_address(IBC_CLIENT)?;
+    if info.sender != ibc_client {
+        return Err(DexError::UnauthorizedCallback(id.to_owned()));
+    }
+    Ok(())
+}
+
+/// Handle the callback of an IBC action, of the balance requests that return its proceeds or of a remote quote request
+pub fn ibc_callback_handler(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    adapter: DexAdapter,
+    id: String,
+    ack: StdAck,
+) -> DexResult {
+    match parse_callback_id(&id) {
+        Some((IBC_DEX_ID, action_id)) => {
+            action_callback(deps, env, info, adapter, id, action_id, ack)
+        }
+        Some((IBC_DEX_QUOTE_ID, quote_id)) => {
+            quote_callback(deps, env, info, adapter, id, quote_id, ack)
+        }
+        Some((IBC_DEX_BALANCE_ID, action_id)) => {
+            balances_callback(deps, env, info, adapter, id, action_id, ack)
+        }
+        Some((_, action_id)) => return_callback(deps, env, info, adapter, id, action_id, ack),
+        None => Err(DexError::UnauthorizedCallback(id)),
+    }
+}
+
+/// Store the balances of the remote account before an action whose proceeds are returned and send the action
+fn balances_callback(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    mut adapter: DexAdapter,
+    id: String,
+    action_id: u64,
+    ack: StdAck,
+) -> DexResult {
+    let mut pending = PENDING_IBC_ACTIONS.load(deps.storage, action_id)?;
+    assert_ibc_client(&deps, &info, &mut adapter, &pending.account, &id)?;
+
+    let proxy = pending.account.proxy.clone();
+    let mut action = IBC_ACTIONS.load(deps.storage, (&proxy, action_id))?;
+    let response = Response::new()
+        .add_attribute("ibc_action_id", action_id.to_string())
+        .add_attribute("account", &proxy);
+    let BalancesResponse { balances, .. } = match &ack {
+        StdAck::Result(data) => from_binary(data)?,
+        StdAck::Error(error) => {
+            // the action wasn't sent, its assets are still on the account
+            PENDING_IBC_ACTIONS.remove(deps.storage, action_id);
+            action.status = IbcActionStatus::Failed {
+                error: error.clone(),
+            };
+            IBC_ACTIONS.save(deps.storage, (&proxy, action_id), &action)?;
+            return Ok(match pending.callback_receiver {
+                Some(receiver) => response.add_submessage(forward_callback(receiver, ack)?),
+                None => response,
+            });
+        }
+    };
+    pending.balances_before = balances;
+    PENDING_IBC_ACTIONS.save(deps.storage, action_id, &pending)?;
+
+    let ans = adapter.name_service(deps.as_ref());
+    let callback = CallbackInfo {
+        id: ibc_callback_id(IBC_DEX_ID, action_id),
+        receiver: env.contract.address.into_string(),
+    };
+    let proxy_msg = ibc_request_msg(
+        deps.as_ref(),
+        ans.host(),
+        &proxy,
+        action.dex,
+        &action.action,
+        pending.deadline,
+        Some(callback),
+    )?;
+    Ok(response.add_message(proxy_msg))
+}
+
+/// Mark the IBC action as succeeded or failed, request the balances of the remote account if its proceeds are returned
+/// and forward the callback to the contract that requested it
+fn action_callback(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    mut adapter: DexAdapter,
+    id: String,
+    action_id: u64,
+    ack: StdAck,
+) -> DexResult {
+    let pending = PENDING_IBC_ACTIONS.load(deps.storage, action_id)?;
+    assert_ibc_client(&deps, &info, &mut adapter, &pending.account, &id)?;
+    PENDING_IBC_ACTIONS.remove(deps.storage, action_id);
+
+    let proxy = pending.account.proxy.clone();
+    let status = match &ack {
+        StdAck::Result(_) => IbcActionStatus::Succeeded,
+        StdAck::Error(error) => IbcActionStatus::Failed {
+            error: error.clone(),
+        },
+    };
+    let mut action = IBC_ACTIONS.load(deps.storage, (&proxy, action_id))?;
+    action.status = status;
+    IBC_ACTIONS.save(deps.storage, (&proxy, action_id), &action)?;
+
+    let mut response = Response::new()
+        .add_attribute("ibc_action_id", action_id.to_string())
+        .add_attribute("account", &proxy);
+
+    // the output of the action is returned when it succeeded and its input when it failed
+    let assets = returned_assets(
+        &action.dex,
+        &action.action,
+        matches!(ack, StdAck::Result(_)),
+    );
+    if pending.return_proceeds && !assets.is_empty() {
+        // the amounts are only known on the host, the transfers are sent once its balances are received
+        response = response.add_message(balances_request_msg(
+            &proxy,
+            action.dex.clone(),
+            ibc_callback_id(IBC_DEX_RETURN_ID, action_id),
+            &env.contract.address,
+        )?);
+        PENDING_RETURNS.save(
+            deps.storage,
+            action_id,
+            &PendingReturn {
+                account: pending.account,
+                host_chain: action.dex,
+                assets,
+                balances_before: pending.balances_before,
+            },
+        )?;
+    }
+
+    if let Some(receiver) = pending.callback_receiver {
+        response = response.add_submessage(forward_callback(receiver, ack)?);
+    }
+    Ok(response)
+}
+
+/// Transfer what the action added to the balances of the remote account in the returned assets back to the account
+fn return_callback(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    mut adapter: DexAdapter,
+    id: String,
+    action_id: u64,
+    ack: StdAck,
+) -> DexResult {
+    let pending = PENDING_RETURNS.load(deps.storage, action_id)?;
+    assert_ibc_client(&deps, &info, &mut adapter, &pending.account, &id)?;
+    PENDING_RETURNS.remove(deps.storage, action_id);
+
+    let response = Response::new().add_attribute("ibc_action_id", action_id.to_string());
+    let BalancesResponse { balances, .. } = match ack {
+        StdAck::Result(data) => from_binary(&data)?,
+        StdAck::Error(error) => return Ok(response.add_attribute("error", error)),
+    };
+
+    // the ics20 channel to the host and its end on the host, which the transfers are sent over
+    let ans = adapter.name_service(deps.as_ref());
+    let channel = ans.query(&ChannelEntry {
+        connected_chain: pending.host_chain.clone(),
+        protocol: ICS20.to_string(),
+    })?;
+    let response: ChannelResponse = deps.querier.query(
+        &IbcQuery::Channel {
+            channel_id: channel.clone(),
+            port_id: Some("transfer".to_owned()),
+        }
+        .into(),
+    )?;
+    let Some(ics20_channel) = response.channel else {
+        return Err(DexError::UnknownIbcChannel(channel));
+    };
+    let host_channel = ics20_channel.counterparty_endpoint.channel_id;
+
+    let mut transfers: Vec<CosmosMsg> = vec![];
+    for asset in pending.assets {
+        // only native assets are transferred over ics20
+        let Ok(AssetInfo::Native(denom)) = asset.resolve(&deps.querier, ans.host()) else {
+            continue;
+        };
+        transfers.extend(
+            balances
+                .iter()
+                .filter(|coin| {
+                    ibc_denom(&channel, &coin.denom) == denom
+                        || coin.denom == ibc_denom(&host_channel, &denom)
+                })
+                .filter_map(|coin| {
+                    // funds that the remote account held before the action stay on the host
+                    let before = pending
+                        .balances_before
+                        .iter()
+                        .find(|before| before.denom == coin.denom)
+                        .map(|before| before.amount)
+                        .unwrap_or_default();
+                    let amount = coin.amount.saturating_sub(before);
+                    (!amount.is_zero()).then(|| {
+                        IbcMsg::Transfer {
+                            channel_id: host_channel.clone(),
+                            to_address: pending.account.proxy.to_string(),
+                            amount: Coin::new(amount.u128(), &coin.denom),
+                            timeout: IbcTimeout::with_timestamp(
+                                env.block.time.plus_seconds(RETURN_TIMEOUT),
+                            ),
+                        }
+                        .into()
+                    })
+                }),
+        );
+    }
+    if transfers.is_empty() {
+        return Ok(response.add_attribute("returned", "none"));
+    }
+
+    let ibc_msg = IbcClientMsg::SendPacket {
+        host_chain: pending.host_chain,
+        action: HostAction::Dispatch { msgs: transfers },
+        callback_info: None,
+        retries: ACTION_RETRIES,
+    };
+    Ok(response.add_message(wasm_execute(
+        pending.account.proxy,
+        &ProxyExecuteMsg::IbcAction {
+            msgs: vec![ibc_msg],
+        },
+        vec![],
+    )?))
+}
+
+/// Assets that an IBC action returns: its output when it succeeded or its input when it failed.
+/// Assets that are only known on the host, like the leftover of paired assets, stay on the host.
+fn returned_assets(host_chain: &str, action: &DexAction, succeeded: bool) -> Vec<AssetEntry> {
+    let lp_token =
+        |assets: Vec<AssetEntry>| -> AssetEntry { LpToken::new(host_chain, assets).into() };
+    match (action, succeeded) {
+        (DexAction::ProvideLiquidity { assets, .. }, true) => {
+            vec![lp_token(assets.iter().map(|a| a.name.clone()).collect())]
+        }
+        (DexAction::ProvideLiquidity { assets, .. }, false) => {
+            assets.iter().map(|a| a.name.clone()).collect()
+        }
+        (
+            DexAction::ProvideLiquiditySymmetric {
+                offer_asset,
+                paired_assets,
+                ..
+            },
+            true,
+        ) => vec![lp_token(
+            std::iter::once(offer_asset.name.clone())
+                .chain(paired_assets.iter().cloned())
+                .collect(),
+        )],
+        (
+            DexAction::ProvideLiquiditySymmetric {
+                offer_asset,
+                paired_assets,
+                ..
+            },
+            false,
+        ) => std::iter::once(offer_asset.name.clone())
+            .chain(paired_assets.iter().cloned())
+            .collect(),
+        (DexAction::WithdrawLiquidity { lp_token, .. }, true) => {
+            AnsEntryConvertor::new(lp_token.clone())
+                .lp_token()
+                .map(|lp| lp.assets)
+                .unwrap_or_default()
+        }
+        (DexAction::WithdrawLiquiditySingleAsset { ask_asset, .. }, true) => {
+            vec![ask_asset.clone()]
+        }
+        (
+            DexAction::WithdrawLiquidity { lp_token, .. }
+            | DexAction::WithdrawLiquiditySingleAsset { lp_token, .. },
+            false,
+        ) => vec![lp_token.clone()],
+        (DexAction::Swap { ask_asset, .. }, true) => vec![ask_asset.clone()],
+        (DexAction::Swap { offer_asset, .. }, false) => vec![offer_asset.name.clone()],
+        (DexAction::CustomSwap { ask_assets, .. }, true) => {
+            ask_assets.iter().map(|a| a.name.clone()).collect()
+        }
+        (DexAction::CustomSwap { offer_assets, .. }, false) => {
+            offer_assets.iter().map(|a| a.name.clone()).collect()
+        }
+        (
+            DexAction::CreatePool {
+                assets,
+                initial_liquidity: Some(_),
+                ..
+            },
+            true,
+        ) => vec![lp_token(assets.clone())],
+        (
+            DexAction::CreatePool {
+                initial_liquidity, ..
+            },
+            false,
+        ) => initial_liquidity
+            .iter()
+            .flatten()
+            .map(|a| a.name.clone())
+            .collect(),
+        // raw actions aren't executed over IBC
+        _ => vec![],
+    }
+}
+
+/// Denom of an asset after it's transferred over an ics20 channel, given the channel on the receiving chain
+fn ibc_denom(channel: &str, denom: &str) -> String {
+    let hash = Sha256::digest(format!("transfer/{channel}/{denom}"));
+    let hash: String = hash.iter().map(|byte| format!("{byte:02X}")).collect();
+    format!("ibc/{hash}")
+}
+
+/// Record that the contract that requested an IBC action failed to handle its callback
+pub fn ibc_callback_forward_reply(
+    _deps: DepsMut,
+    _env: Env,
+    _adapter: DexAdapter,
+    reply: Reply,
+) -> DexResult {
+    let error = reply.result.unwrap_err();
+    Ok(Response::new().add_attribute("callback_error", error))
+}
+
+/// Store the quote that the remote dex adapter returned
+fn quote_callback(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    mut adapter: DexAdapter,
+    id: String,
+    quote_id: u64,
+    ack: StdAck,
+) -> DexResult {
+    let pending = PENDING_REMOTE_QUOTES.load(deps.storage, quote_id)?;
+    assert_ibc_client(&deps, &info, &mut adapter, &pending.account, &id)?;
+    PENDING_REMOTE_QUOTES.remove(deps.storage, quote_id);
+
+    let PendingQuote {
+        dex,
+        offer_asset,
+        ask_asset,
+        ..
+    } = pending;
+    let response = Response::new()
+        .add_attribute("quote_id", quote_id.to_string())
+        .add_attribute("remote_quote", &dex);
+    let IbcQueryResponse { results } = match ack {
+        StdAck::Result(data) => from_binary(&data)?,
+        StdAck::Error(error) => return Ok(response.add_attribute("error", error)),
+    };
+    // the only query of the request is the simulation
+    let Some(result) = results.first() else {
+        return Ok(response.add_attribute("error", "no query result"));
+    };
+    let quote: SimulateSwapResponse = from_binary(result)?;
+
+    let pairing = DexAssetPairing::new(offer_asset.name.clone(), ask_asset, &dex);
+    REMOTE_QUOTES.save(
+        deps.storage,
+        &pairing,
+        &RemoteQuote {
+            offer_asset,
+            quote,
+            updated_at: env.block.time,
+        },
+    )?;
+
+    Ok(response)
+}
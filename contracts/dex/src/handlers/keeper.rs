@@ -0,0 +1,261 @@
+use crate::contract::{DexAdapter, DexResult};
+use crate::exchanges::exchange_resolver;
+use crate::handlers::execute::resolve_local_action_msgs;
+use crate::msg::{
+    DcaExecution, DcaSchedule, DexAction, DexKeeperMsg, FeeMode, OfferAsset, Order, TwapOrder,
+};
+use crate::state::{dca_schedules, orders, twap_orders, OrderState, DCA_HISTORY};
+use abstract_core::objects::{AnsAsset, AssetEntry};
+use abstract_dex_adapter_traits::DexError;
+use abstract_sdk::features::AbstractNameService;
+use abstract_sdk::Execution;
+use cosmwasm_std::{CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo, Response, Uint128};
+use cw_asset::Asset;
+
+pub fn keeper_handler(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    adapter: DexAdapter,
+    msg: DexKeeperMsg,
+) -> DexResult {
+    match msg {
+        DexKeeperMsg::ExecuteOrder { order_id } => {
+            execute_order(deps, env, info, adapter, order_id)
+        }
+        DexKeeperMsg::ExecuteDca { dca_id } => execute_dca(deps, env, adapter, dca_id),
+        DexKeeperMsg::ExecuteTwapSlice { twap_id } => {
+            execute_twap_slice(deps, env, adapter, twap_id)
+        }
+    }
+}
+
+/// Swap the offer asset of an order whose trigger is met and pay the keeper tip to the sender
+fn execute_order(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    mut adapter: DexAdapter,
+    order_id: u64,
+) -> DexResult {
+    let OrderState {
+        account,
+        dex,
+        order,
+    } = orders().load(deps.storage, order_id)?;
+    // keepers don't act on behalf of the account
+    adapter.target_account = Some(account.clone());
+
+    let Order {
+        mut offer_asset,
+        ask_asset,
+        trigger,
+        max_spread,
+        keeper_tip,
+    } = order;
+    let tip = AnsAsset::new(offer_asset.name.clone(), keeper_tip);
+    offer_asset.amount -= keeper_tip;
+
+    let return_amount = simulate_return(deps.as_ref(), &adapter, &dex, &offer_asset, &ask_asset)?;
+    let price = Decimal::from_ratio(return_amount, offer_asset.amount);
+    if !trigger.is_met(price) {
+        return Err(DexError::OrderNotTriggered(order_id, price));
+    }
+    orders().remove(deps.storage, order_id)?;
+
+    // the spread is measured from the trigger price, so keepers can't move the pool to trigger the order
+    // and execute it at a worse price. The belief price is in offer asset per ask asset.
+    let belief_price = trigger.price().inv();
+    let mut msgs = swap_msgs(
+        deps.branch(),
+        &env,
+        &adapter,
+        &dex,
+        offer_asset,
+        ask_asset,
+        Some(max_spread),
+        belief_price,
+    )?;
+    if !tip.amount.is_zero() {
+        let tip: Asset = adapter.name_service(deps.as_ref()).query(&tip)?;
+        msgs.push(tip.transfer_msg(&info.sender)?);
+    }
+
+    let proxy_msg = adapter
+        .executor(deps.as_ref())
+        .execute(msgs.into_iter().map(Into::into).collect())?;
+    Ok(Response::new()
+        .add_message(proxy_msg)
+        .add_attribute("account", account.proxy)
+        .add_attribute("order_id", order_id.to_string())
+        .add_attribute("keeper", info.sender))
+}
+
+/// Execute the swap of a schedule that is due and above its minimum price, and record it in its history
+fn execute_dca(mut deps: DepsMut, env: Env, mut adapter: DexAdapter, dca_id: u64) -> DexResult {
+    let mut state = dca_schedules().load(deps.storage, dca_id)?;
+    if state.paused {
+        return Err(DexError::DcaPaused(dca_id));
+    }
+    let now = env.block.time.seconds();
+    if now < state.next_execution {
+        return Err(DexError::DcaNotDue(dca_id, state.next_execution));
+    }
+    // keepers don't act on behalf of the account
+    adapter.target_account = Some(state.account.clone());
+
+    let DcaSchedule {
+        offer_asset,
+        ask_asset,
+        interval,
+        min_price,
+        max_spread,
+    } = state.schedule.clone();
+    let expected_return = simulate_return(
+        deps.as_ref(),
+        &adapter,
+        &state.dex,
+        &offer_asset,
+        &ask_asset,
+    )?;
+    let price = Decimal::from_ratio(expected_return, offer_asset.amount);
+    if price < min_price {
+        return Err(DexError::DcaPriceLimit(dca_id, price, min_price));
+    }
+    let msgs = swap_msgs(
+        deps.branch(),
+        &env,
+        &adapter,
+        &state.dex,
+        offer_asset.clone(),
+        ask_asset,
+        Some(max_spread),
+        None,
+    )?;
+
+    DCA_HISTORY.save(
+        deps.storage,
+        (dca_id, now),
+        &DcaExecution {
+            time: now,
+            offered: offer_asset.amount,
+            expected_return,
+        },
+    )?;
+    // intervals that were missed are skipped
+    state.next_execution = now + interval - (now - state.next_execution) % interval;
+    state.executions += 1;
+    dca_schedules().save(deps.storage, dca_id, &state)?;
+
+    let proxy_msg = adapter
+        .executor(deps.as_ref())
+        .execute(msgs.into_iter().map(Into::into).collect())?;
+    Ok(Response::new()
+        .add_message(proxy_msg)
+        .add_attribute("account", state.account.proxy)
+        .add_attribute("dca_id", dca_id.to_string()))
+}
+
+/// Execute the next slice of a time-sliced order if it's due and its price is above the limit of the order
+fn execute_twap_slice(
+    mut deps: DepsMut,
+    env: Env,
+    mut adapter: DexAdapter,
+    twap_id: u64,
+) -> DexResult {
+    let mut state = twap_orders().load(deps.storage, twap_id)?;
+    let slice_amount = state.next_slice();
+    if slice_amount.is_zero() {
+        return Err(DexError::TwapCompleted(twap_id));
+    }
+    let now = env.block.time.seconds();
+    if now < state.next_execution {
+        return Err(DexError::TwapNotDue(twap_id, state.next_execution));
+    }
+    // keepers don't act on behalf of the account
+    adapter.target_account = Some(state.account.clone());
+
+    let TwapOrder {
+        offer_asset,
+        ask_asset,
+        min_price,
+        interval,
+        max_spread,
+        ..
+    } = state.twap.clone();
+    let slice = OfferAsset::new(offer_asset.name, slice_amount);
+    let expected_return = simulate_return(deps.as_ref(), &adapter, &state.dex, &slice, &ask_asset)?;
+    let price = Decimal::from_ratio(expected_return, slice_amount);
+    if price < min_price {
+        return Err(DexError::TwapPriceLimit(twap_id, price, min_price));
+    }
+    let msgs = swap_msgs(
+        deps.branch(),
+        &env,
+        &adapter,
+        &state.dex,
+        slice,
+        ask_asset,
+        Some(max_spread),
+        None,
+    )?;
+
+    state.offered += slice_amount;
+    state.expected_return += expected_return;
+    state.slices += 1;
+    state.next_execution = now + interval;
+    twap_orders().save(deps.storage, twap_id, &state)?;
+
+    let proxy_msg = adapter
+        .executor(deps.as_ref())
+        .execute(msgs.into_iter().map(Into::into).collect())?;
+    Ok(Response::new()
+        .add_message(proxy_msg)
+        .add_attribute("account", state.account.proxy)
+        .add_attribute("twap_id", twap_id.to_string())
+        .add_attribute("offered", slice_amount)
+        .add_attribute("expected_return", expected_return))
+}
+
+/// Return of a swap of the offer asset on the dex, before the usage fee
+fn simulate_return(
+    deps: Deps,
+    adapter: &DexAdapter,
+    dex: &str,
+    offer_asset: &OfferAsset,
+    ask_asset: &AssetEntry,
+) -> DexResult<Uint128> {
+    let exchange = exchange_resolver::resolve_exchange(dex)?;
+    let ans = adapter.name_service(deps);
+    let pool = exchange.pair_address(
+        deps,
+        ans.host(),
+        (offer_asset.name.clone(), ask_asset.clone()),
+    )?;
+    let (return_amount, ..) =
+        exchange.simulate_swap(deps, pool, ans.query(offer_asset)?, ans.query(ask_asset)?)?;
+    Ok(return_amount)
+}
+
+/// Messages of a swap for the account, checked by the protections of the account like any other swap
+#[allow(clippy::too_many_arguments)]
+fn swap_msgs(
+    deps: DepsMut,
+    env: &Env,
+    adapter: &DexAdapter,
+    dex: &str,
+    offer_asset: OfferAsset,
+    ask_asset: AssetEntry,
+    max_spread: Option<Decimal>,
+    belief_price: Option<Decimal>,
+) -> DexResult<Vec<CosmosMsg>> {
+    let swap = DexAction::Swap {
+        offer_asset,
+        ask_asset,
+        max_spread,
+        belief_price,
+        recipient: None,
+        fee_mode: FeeMode::default(),
+    };
+    resolve_local_action_msgs(deps, env, adapter, dex, swap)
+}
@@ -1,5 +1,16 @@
 mod execute;
+pub(crate) mod guard;
+mod ibc_callback;
 mod instantiate;
+mod keeper;
 mod query;
+mod reply;
 
-pub use {execute::execute_handler, instantiate::instantiate_handler, query::query_handler};
+pub use {
+    execute::execute_handler,
+    ibc_callback::{ibc_callback_forward_reply, ibc_callback_handler, is_request_callback},
+    instantiate::instantiate_handler,
+    keeper::keeper_handler,
+    query::query_handler,
+    reply::{batch_step_reply, create_pool_reply, forward_reply},
+};
@@ -1,19 +1,48 @@
+use crate::handlers::execute::{
+    assert_deadline, ibc_request_msg, local_batch_steps, offered_amounts_mut,
+};
+use crate::handlers::guard::{
+    assert_oracle_deviation, assert_price_impact, assert_spend_limits, guarded_swap_simulation,
+    limited_spends,
+};
 use crate::handlers::query::exchange_resolver::is_over_ibc;
 
 use crate::exchanges::exchange_resolver::resolve_exchange;
 
 use crate::msg::{
-    DexExecuteMsg, DexQueryMsg, GenerateMessagesResponse, OfferAsset, SimulateSwapResponse,
+    AccountStatsResponse, AssetListsResponse, DcaHistoryResponse, DcaInfo, DcaSchedulesResponse,
+    DexAction, DexExecuteMsg, DexName, DexQueryMsg, DryRunResponse, ExecuteMsg, FeeMode,
+    GenerateMessagesResponse, IbcActionsResponse, LpEntriesResponse, LpEntry, LpEntryInfo,
+    LpValueResponse, MaxPriceImpactResponse, OfferAsset, OracleConfigResponse, OrderInfo,
+    OrdersResponse, Position, PositionsResponse, RemoteQuoteResponse, SimulateSwapResponse,
+    SpendAllowance, SpendLimitsResponse, TopPairsResponse, TwapInfo, TwapOrdersResponse,
+};
+use crate::state::{
+    dca_schedules, orders, pair_stats, twap_orders, ACCOUNT_STATS, ASSET_LISTS, DCA_HISTORY,
+    IBC_ACTIONS, LP_ENTRIES, MAX_PRICE_IMPACT, ORACLE_CONFIG, REMOTE_QUOTES, SPEND_LIMITS,
+    SWAP_FEE,
 };
-use crate::state::SWAP_FEE;
 use crate::{
     contract::{DexAdapter, DexResult},
     exchanges::exchange_resolver,
 };
-use abstract_core::objects::{AssetEntry, DexAssetPairing};
-use abstract_dex_adapter_traits::DexError;
+use abstract_core::adapter::AdapterRequestMsg;
+use abstract_core::ans_host::{AssetListResponse, QueryMsg as AnsHostQueryMsg};
+use abstract_core::objects::{
+    AnsAsset, AnsEntryConvertor, AssetEntry, DexAssetPairing, LpToken, PoolAddress,
+};
+use abstract_dex_adapter_traits::{DexCommand, DexError};
 use abstract_sdk::features::AbstractNameService;
-use cosmwasm_std::{to_binary, Binary, Deps, Env, StdError};
+use cosmwasm_std::{
+    to_binary, wasm_execute, Addr, Binary, Decimal, Deps, Env, Order, StdError, StdResult, Uint128,
+};
+use cw_asset::{Asset, AssetInfo};
+use cw_storage_plus::Bound;
+
+const DEFAULT_LIMIT: u8 = 10;
+const MAX_LIMIT: u8 = 30;
+/// Assets of the ANS that are scanned for LP tokens per positions query
+const MAX_SCANNED_ASSETS: usize = 90;
 
 pub fn query_handler(
     deps: Deps,
@@ -26,25 +55,300 @@ pub fn query_handler(
             offer_asset,
             ask_asset,
             dex,
-        } => simulate_swap(deps, env, adapter, offer_asset, ask_asset, dex.unwrap()),
-        DexQueryMsg::GenerateMessages { message } => {
-            match message {
-                DexExecuteMsg::Action { dex, action } => {
-                    let (local_dex_name, is_over_ibc) = is_over_ibc(env, &dex)?;
-                    // if exchange is on an app-chain, execute the action on the app-chain
-                    if is_over_ibc {
-                        return Err(DexError::IbcMsgQuery);
-                    }
-                    let exchange = exchange_resolver::resolve_exchange(&local_dex_name)?;
-                    let (messages, _) = crate::adapter::DexAdapter::resolve_dex_action(
-                        adapter, deps, action, exchange,
-                    )?;
-                    to_binary(&GenerateMessagesResponse { messages }).map_err(Into::into)
+            fee_mode,
+        } => simulate_swap(
+            deps,
+            env,
+            adapter,
+            offer_asset,
+            ask_asset,
+            dex.unwrap(),
+            fee_mode,
+        ),
+        DexQueryMsg::GenerateMessages {
+            message,
+            proxy_address,
+        } => generate_messages(deps, env, adapter, message, proxy_address),
+        DexQueryMsg::AccountStats {
+            account,
+            start_after,
+            limit,
+        } => account_stats(deps, account, start_after, limit),
+        DexQueryMsg::TopPairs { start_after, limit } => top_pairs(deps, start_after, limit),
+        DexQueryMsg::RemoteQuote {
+            dex,
+            offer_asset,
+            ask_asset,
+        } => remote_quote(deps, env, dex, offer_asset, ask_asset),
+        DexQueryMsg::IbcActions {
+            account,
+            start_after,
+            limit,
+        } => ibc_actions(deps, account, start_after, limit),
+        DexQueryMsg::MaxPriceImpact { account } => {
+            let account = deps.api.addr_validate(&account)?;
+            let max_price_impact = MAX_PRICE_IMPACT.may_load(deps.storage, &account)?;
+            to_binary(&MaxPriceImpactResponse { max_price_impact }).map_err(Into::into)
+        }
+        DexQueryMsg::OracleConfig { account } => {
+            let account = deps.api.addr_validate(&account)?;
+            let config = ORACLE_CONFIG.may_load(deps.storage, &account)?;
+            to_binary(&OracleConfigResponse { config }).map_err(Into::into)
+        }
+        DexQueryMsg::Positions {
+            account,
+            start_after,
+            limit,
+        } => positions(deps, env, adapter, account, start_after, limit),
+        DexQueryMsg::LpEntries {
+            account,
+            start_after,
+            limit,
+        } => lp_entries(deps, adapter, account, start_after, limit),
+        DexQueryMsg::Orders {
+            account,
+            start_after,
+            limit,
+        } => open_orders(deps, account, start_after, limit),
+        DexQueryMsg::DcaSchedules {
+            account,
+            start_after,
+            limit,
+        } => dca_schedules_of(deps, account, start_after, limit),
+        DexQueryMsg::DcaHistory {
+            dca_id,
+            start_after,
+            limit,
+        } => dca_history(deps, dca_id, start_after, limit),
+        DexQueryMsg::TwapOrders {
+            account,
+            start_after,
+            limit,
+        } => twap_orders_of(deps, account, start_after, limit),
+        DexQueryMsg::LpValue {
+            dex,
+            lp_token,
+            amount,
+            quote_asset,
+        } => lp_value(deps, env, adapter, dex, lp_token, amount, quote_asset),
+        DexQueryMsg::DryRun {
+            dex,
+            action,
+            account,
+        } => dry_run(deps, env, adapter, dex, action, account),
+        DexQueryMsg::SpendLimits {
+            account,
+            start_after,
+            limit,
+        } => spend_limits(deps, env, account, start_after, limit),
+        DexQueryMsg::AssetLists { account } => {
+            let account = deps.api.addr_validate(&account)?;
+            let lists = ASSET_LISTS.may_load(deps.storage, &account)?;
+            to_binary(&AssetListsResponse { lists }).map_err(Into::into)
+        }
+    }
+}
+
+fn account_stats(
+    deps: Deps,
+    account: String,
+    start_after: Option<(DexName, AssetEntry)>,
+    limit: Option<u8>,
+) -> DexResult<Binary> {
+    let account = deps.api.addr_validate(&account)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let min = start_after
+        .as_ref()
+        .map(|(dex, asset)| Bound::exclusive((dex.as_str(), asset)));
+
+    let stats = ACCOUNT_STATS
+        .sub_prefix(&account)
+        .range(deps.storage, min, None, Order::Ascending)
+        .take(limit)
+        .map(|entry| entry.map(|((dex, asset), stats)| (dex, asset, stats)))
+        .collect::<StdResult<_>>()?;
+    to_binary(&AccountStatsResponse { stats }).map_err(Into::into)
+}
+
+fn spend_limits(
+    deps: Deps,
+    env: Env,
+    account: String,
+    start_after: Option<AssetEntry>,
+    limit: Option<u8>,
+) -> DexResult<Binary> {
+    let account = deps.api.addr_validate(&account)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let min = start_after.as_ref().map(Bound::exclusive);
+    let now = env.block.time.seconds();
+
+    let limits = SPEND_LIMITS
+        .prefix(&account)
+        .range(deps.storage, min, None, Order::Ascending)
+        .take(limit)
+        .map(|entry| {
+            entry.map(|(asset, state)| {
+                let allowance = SpendAllowance {
+                    spent: state.spent_at(now),
+                    remaining: state.remaining_at(now),
+                    resets_at: state.resets_at(now),
+                    limit: state.limit,
+                };
+                (asset, allowance)
+            })
+        })
+        .collect::<StdResult<_>>()?;
+    to_binary(&SpendLimitsResponse { limits }).map_err(Into::into)
+}
+
+fn top_pairs(
+    deps: Deps,
+    start_after: Option<(u64, DexAssetPairing)>,
+    limit: Option<u8>,
+) -> DexResult<Binary> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let pair_stats = pair_stats();
+    // continue from the swap count the client saw, the count of the pair may have changed since
+    let max = start_after
+        .as_ref()
+        .map(|(swaps, pairing)| Bound::exclusive((*swaps, pairing)));
+
+    let pairs = pair_stats
+        .idx
+        .swaps
+        .range(deps.storage, None, max, Order::Descending)
+        .take(limit)
+        .collect::<StdResult<_>>()?;
+    to_binary(&TopPairsResponse { pairs }).map_err(Into::into)
+}
+
+fn generate_messages(
+    deps: Deps,
+    env: Env,
+    adapter: &DexAdapter,
+    message: DexExecuteMsg,
+    proxy_address: Option<String>,
+) -> DexResult<Binary> {
+    let require_proxy = |msg_kind: &str| {
+        proxy_address
+            .as_ref()
+            .ok_or_else(|| DexError::ProxyAddressRequired(msg_kind.to_owned()))
+            .and_then(|proxy| deps.api.addr_validate(proxy).map_err(Into::into))
+    };
+    // the actions of an account are checked against its asset lists
+    let assert_assets_allowed = |action: &DexAction, dex: &str| -> DexResult<()> {
+        match &proxy_address {
+            Some(proxy) => crate::adapter::DexAdapter::assert_assets_allowed(
+                adapter,
+                deps,
+                &deps.api.addr_validate(proxy)?,
+                action,
+                dex,
+            ),
+            None => Ok(()),
+        }
+    };
+
+    let messages = match message {
+        DexExecuteMsg::Action {
+            dex,
+            action,
+            return_proceeds,
+            deadline,
+        } => {
+            let (local_dex_name, is_over_ibc) = is_over_ibc(env.clone(), &dex)?;
+            assert_deadline(&env, deadline, is_over_ibc)?;
+            // if exchange is on an app-chain, execute the action on the app-chain
+            if is_over_ibc {
+                if return_proceeds {
+                    return Err(DexError::ReturnProceedsNotGenerated {});
+                }
+                let proxy = require_proxy("IBC actions")?;
+                assert_assets_allowed(&action, &local_dex_name)?;
+                let ans = adapter.name_service(deps);
+                // callbacks are only tracked for actions executed through the adapter
+                vec![ibc_request_msg(
+                    deps,
+                    ans.host(),
+                    &proxy,
+                    local_dex_name,
+                    &action,
+                    deadline,
+                    None,
+                )?]
+            } else {
+                if return_proceeds {
+                    return Err(DexError::ReturnProceedsNotOverIbc {});
                 }
-                _ => Err(DexError::InvalidGenerateMessage {}),
+                let exchange = exchange_resolver::resolve_exchange(&local_dex_name)?;
+                assert_assets_allowed(&action, exchange.name())?;
+                let resolved = crate::adapter::DexAdapter::resolve_dex_action(
+                    adapter, deps, action, exchange,
+                )?;
+                // outputs are forwarded in replies of the adapter
+                if resolved.forward.is_some() {
+                    return Err(DexError::RecipientNotSupported(local_dex_name));
+                }
+                if resolved.fee_asset.is_some() {
+                    return Err(DexError::AskAssetFeeNotSupported(local_dex_name));
+                }
+                resolved.msgs
             }
         }
-    }
+        msg @ DexExecuteMsg::UpdateFee { .. } => {
+            // fee updates are requested by the proxy itself
+            let proxy = require_proxy("fee updates")?;
+            let request = ExecuteMsg::Module(AdapterRequestMsg {
+                proxy_address: Some(proxy.into_string()),
+                request: msg,
+            });
+            vec![wasm_execute(env.contract.address, &request, vec![])?.into()]
+        }
+        // quote callbacks are only tracked for requests executed through the adapter
+        DexExecuteMsg::RequestRemoteQuote { .. } => return Err(DexError::InvalidGenerateMessage),
+        // account settings can only be changed by the owner of the account, not its proxy
+        DexExecuteMsg::SetMaxPriceImpact { .. }
+        | DexExecuteMsg::SetOracleConfig { .. }
+        | DexExecuteMsg::SetAssetLists { .. }
+        | DexExecuteMsg::SetSpendLimit { .. } => return Err(DexError::InvalidGenerateMessage),
+        // orders and schedules are stored by the adapter
+        DexExecuteMsg::PlaceOrder { .. }
+        | DexExecuteMsg::CancelOrder { .. }
+        | DexExecuteMsg::CreateDca { .. }
+        | DexExecuteMsg::PauseDca { .. }
+        | DexExecuteMsg::ResumeDca { .. }
+        | DexExecuteMsg::CancelDca { .. }
+        | DexExecuteMsg::PlaceTwap { .. }
+        | DexExecuteMsg::CancelTwap { .. } => return Err(DexError::InvalidGenerateMessage),
+        DexExecuteMsg::Batch { actions } => {
+            let mut steps = local_batch_steps(env, actions)?;
+            // outputs of previous steps are measured in replies of the adapter
+            if steps.iter_mut().skip(1).any(|(_, action)| {
+                offered_amounts_mut(action)
+                    .iter()
+                    .any(|(_, amount)| amount.is_zero())
+            }) {
+                return Err(DexError::ChainedBatchMessages {});
+            }
+            let mut messages = vec![];
+            for (dex, action) in steps {
+                let exchange = exchange_resolver::resolve_exchange(&dex)?;
+                assert_assets_allowed(&action, exchange.name())?;
+                let resolved = crate::adapter::DexAdapter::resolve_dex_action(
+                    adapter, deps, action, exchange,
+                )?;
+                if resolved.forward.is_some() {
+                    return Err(DexError::RecipientNotSupported(dex));
+                }
+                if resolved.fee_asset.is_some() {
+                    return Err(DexError::AskAssetFeeNotSupported(dex));
+                }
+                messages.extend(resolved.msgs);
+            }
+            messages
+        }
+    };
+    to_binary(&GenerateMessagesResponse { messages }).map_err(Into::into)
 }
 
 pub fn simulate_swap(
@@ -54,6 +358,7 @@ pub fn simulate_swap(
     mut offer_asset: OfferAsset,
     mut ask_asset: AssetEntry,
     dex: String,
+    fee_mode: FeeMode,
 ) -> DexResult<Binary> {
     let exchange = resolve_exchange(&dex).map_err(|e| StdError::generic_err(e.to_string()))?;
     let ans = adapter.name_service(deps);
@@ -80,12 +385,19 @@ pub fn simulate_swap(
         DexAssetPairing::new(offer_asset.name.clone(), ask_asset.clone(), exchange.name());
 
     // compute adapter fee
-    let adapter_fee = fee.compute(offer_asset.amount);
-    offer_asset.amount -= adapter_fee;
+    let mut adapter_fee = Uint128::zero();
+    if fee_mode == FeeMode::OfferAsset {
+        adapter_fee = fee.compute(offer_asset.amount);
+        offer_asset.amount -= adapter_fee;
+    }
 
-    let (return_amount, spread_amount, commission_amount, fee_on_input) = exchange
+    let (mut return_amount, spread_amount, commission_amount, fee_on_input) = exchange
         .simulate_swap(deps, pair_address, swap_offer_asset, ask_asset_info)
         .map_err(|e| StdError::generic_err(e.to_string()))?;
+    if fee_mode == FeeMode::AskAsset {
+        adapter_fee = fee.compute(return_amount);
+        return_amount -= adapter_fee;
+    }
     let commission_asset = if fee_on_input {
         ask_asset
     } else {
@@ -97,6 +409,728 @@ pub fn simulate_swap(
         spread_amount,
         commission: (commission_asset, commission_amount),
         usage_fee: adapter_fee,
+        fee_mode,
     };
     to_binary(&resp).map_err(From::from)
 }
+
+fn dry_run(
+    deps: Deps,
+    env: Env,
+    adapter: &DexAdapter,
+    dex: DexName,
+    action: DexAction,
+    account: String,
+) -> DexResult<Binary> {
+    let proxy = deps.api.addr_validate(&account)?;
+    let (local_dex_name, is_over_ibc) = is_over_ibc(env.clone(), &dex)?;
+    if is_over_ibc {
+        return Err(DexError::DryRunNotSupported(format!("remote dex {dex}")));
+    }
+    let exchange = resolve_exchange(&local_dex_name)?;
+
+    // fail like the execution would
+    crate::adapter::DexAdapter::assert_assets_allowed(
+        adapter,
+        deps,
+        &proxy,
+        &action,
+        exchange.name(),
+    )?;
+    assert_spend_limits(&env, &limited_spends(deps, adapter, &proxy, &action)?)?;
+    if let Some(simulation) = guarded_swap_simulation(deps, adapter, exchange, &proxy, &action)? {
+        assert_price_impact(deps, &proxy, &simulation)?;
+        assert_oracle_deviation(deps, &env, adapter, &proxy, &simulation)?;
+    }
+
+    let ans = adapter.name_service(deps);
+    let (mut response, to_account) = match action {
+        DexAction::Swap {
+            mut offer_asset,
+            mut ask_asset,
+            recipient,
+            fee_mode,
+            ..
+        } => {
+            offer_asset.name.format();
+            ask_asset.format();
+            let pool = exchange.pair_address(
+                deps,
+                ans.host(),
+                (offer_asset.name.clone(), ask_asset.clone()),
+            )?;
+            let response = swap_dry_run(
+                deps,
+                exchange,
+                pool,
+                ans.query(&offer_asset)?,
+                ans.query(&ask_asset)?,
+                fee_mode,
+            )?;
+            (response, recipient.is_none())
+        }
+        DexAction::RawSwap {
+            pool,
+            offer_asset,
+            ask_asset,
+            ..
+        } => {
+            let response = swap_dry_run(
+                deps,
+                exchange,
+                pool.check(deps.api)?,
+                offer_asset.check(deps.api, None)?,
+                ask_asset.check(deps.api, None)?,
+                FeeMode::OfferAsset,
+            )?;
+            (response, true)
+        }
+        DexAction::ProvideLiquidity {
+            mut assets,
+            recipient,
+            ..
+        } => {
+            if assets.len() < 2 {
+                return Err(DexError::TooFewAssets {});
+            }
+            for asset in assets.iter_mut() {
+                asset.name.format();
+            }
+            let pair_assets: Vec<AssetEntry> =
+                assets.iter().map(|a| a.name.clone()).take(2).collect();
+            let pool = exchange.pair_address(
+                deps,
+                ans.host(),
+                (pair_assets[0].clone(), pair_assets[1].clone()),
+            )?;
+            let lp_token: AssetEntry = LpToken::new(exchange.name(), pair_assets).into();
+            let response = provide_liquidity_dry_run(
+                deps,
+                exchange,
+                pool,
+                ans.query(&assets)?,
+                ans.query(&lp_token)?,
+            )?;
+            (response, recipient.is_none())
+        }
+        DexAction::ProvideLiquiditySymmetric {
+            mut offer_asset,
+            mut paired_assets,
+            ..
+        } => {
+            if paired_assets.is_empty() {
+                return Err(DexError::TooFewAssets {});
+            }
+            offer_asset.name.format();
+            for asset in paired_assets.iter_mut() {
+                asset.format();
+            }
+            let pool = exchange.pair_address(
+                deps,
+                ans.host(),
+                (offer_asset.name.clone(), paired_assets[0].clone()),
+            )?;
+            let mut pair_assets = vec![offer_asset.name.clone()];
+            pair_assets.extend(paired_assets.iter().cloned());
+            let lp_token: AssetEntry = LpToken::new(exchange.name(), pair_assets).into();
+
+            // the paired amounts follow the ratio of the reserves
+            let offer_asset = ans.query(&offer_asset)?;
+            let (reserves, _) = exchange.pool_reserves(deps, pool.clone())?;
+            let offer_reserve = reserve_of(&reserves, &offer_asset.info)?;
+            let mut assets = vec![offer_asset.clone()];
+            for info in ans.query(&paired_assets)? {
+                let amount = offer_asset
+                    .amount
+                    .multiply_ratio(reserve_of(&reserves, &info)?, offer_reserve);
+                assets.push(Asset::new(info, amount));
+            }
+            let response =
+                provide_liquidity_dry_run(deps, exchange, pool, assets, ans.query(&lp_token)?)?;
+            (response, true)
+        }
+        DexAction::WithdrawLiquidity {
+            mut lp_token,
+            amount,
+            recipient,
+        } => {
+            lp_token.format();
+            let pair_assets = AnsEntryConvertor::new(lp_token.clone()).lp_token()?.assets;
+            if pair_assets.len() < 2 {
+                return Err(DexError::TooFewAssets {});
+            }
+            let pool = exchange.pair_address(
+                deps,
+                ans.host(),
+                (pair_assets[0].clone(), pair_assets[1].clone()),
+            )?;
+            let lp_token = Asset::new(ans.query(&lp_token)?, amount);
+            let response = withdraw_liquidity_dry_run(deps, exchange, pool, lp_token)?;
+            (response, recipient.is_none())
+        }
+        DexAction::RawWithdrawLiquidity { pool, lp_token } => {
+            let response = withdraw_liquidity_dry_run(
+                deps,
+                exchange,
+                pool.check(deps.api)?,
+                lp_token.check(deps.api, None)?,
+            )?;
+            (response, true)
+        }
+        // the LP token of a raw pool isn't known
+        DexAction::RawProvideLiquidity { .. } => {
+            return Err(DexError::DryRunNotSupported(
+                "raw liquidity provision".into(),
+            ))
+        }
+        DexAction::CustomSwap { .. } => {
+            return Err(DexError::DryRunNotSupported("custom swap".into()))
+        }
+        DexAction::WithdrawLiquiditySingleAsset { .. } => {
+            return Err(DexError::DryRunNotSupported(
+                "single asset liquidity withdrawal".into(),
+            ))
+        }
+        DexAction::CreatePool { .. } => {
+            return Err(DexError::DryRunNotSupported("pool creation".into()))
+        }
+    };
+    if !to_account {
+        response.received.clear();
+    }
+    response.fees.retain(|fee| !fee.amount.is_zero());
+    to_binary(&response).map_err(Into::into)
+}
+
+fn swap_dry_run(
+    deps: Deps,
+    exchange: &dyn DexCommand,
+    pool: PoolAddress,
+    offer_asset: Asset,
+    ask_asset: AssetInfo,
+    fee_mode: FeeMode,
+) -> DexResult<DryRunResponse> {
+    let fee = SWAP_FEE.load(deps.storage)?;
+    let mut usage_fee = Asset::new(offer_asset.info.clone(), 0u128);
+    if fee_mode == FeeMode::OfferAsset {
+        usage_fee.amount = fee.compute(offer_asset.amount);
+    }
+    let swapped = Asset::new(
+        offer_asset.info.clone(),
+        offer_asset.amount - usage_fee.amount,
+    );
+    let (mut return_amount, _, commission, fee_on_input) =
+        exchange.simulate_swap(deps, pool, swapped, ask_asset.clone())?;
+    if fee_mode == FeeMode::AskAsset {
+        usage_fee = Asset::new(ask_asset.clone(), fee.compute(return_amount));
+        return_amount -= usage_fee.amount;
+    }
+    let commission_asset = if fee_on_input {
+        offer_asset.info.clone()
+    } else {
+        ask_asset.clone()
+    };
+    Ok(DryRunResponse {
+        fees: vec![usage_fee, Asset::new(commission_asset, commission)],
+        offered: vec![offer_asset],
+        received: vec![Asset::new(ask_asset, return_amount)],
+    })
+}
+
+/// LP tokens are minted in proportion to the smallest share of the reserves that is provided
+fn provide_liquidity_dry_run(
+    deps: Deps,
+    exchange: &dyn DexCommand,
+    pool: PoolAddress,
+    assets: Vec<Asset>,
+    lp_token: AssetInfo,
+) -> DexResult<DryRunResponse> {
+    let (reserves, total_share) = exchange.pool_reserves(deps, pool)?;
+    if total_share.is_zero() {
+        return Err(DexError::DryRunNotSupported(
+            "liquidity provision to an empty pool".into(),
+        ));
+    }
+    let mut minted: Option<Uint128> = None;
+    for asset in &assets {
+        let share = asset
+            .amount
+            .multiply_ratio(total_share, reserve_of(&reserves, &asset.info)?);
+        minted = Some(minted.map_or(share, |minted| minted.min(share)));
+    }
+    Ok(DryRunResponse {
+        offered: assets,
+        received: vec![Asset::new(lp_token, minted.unwrap_or_default())],
+        fees: vec![],
+    })
+}
+
+/// Withdrawn assets are the share of the reserves of the burned LP tokens
+fn withdraw_liquidity_dry_run(
+    deps: Deps,
+    exchange: &dyn DexCommand,
+    pool: PoolAddress,
+    lp_token: Asset,
+) -> DexResult<DryRunResponse> {
+    let (reserves, total_share) = exchange.pool_reserves(deps, pool)?;
+    if total_share.is_zero() {
+        return Err(DexError::DryRunNotSupported(
+            "liquidity withdrawal from an empty pool".into(),
+        ));
+    }
+    Ok(DryRunResponse {
+        received: lp_underlying(reserves, total_share, lp_token.amount),
+        offered: vec![lp_token],
+        fees: vec![],
+    })
+}
+
+/// Share of the reserves that the LP tokens are redeemable for, the total share must not be zero
+fn lp_underlying(reserves: Vec<Asset>, total_share: Uint128, lp_amount: Uint128) -> Vec<Asset> {
+    reserves
+        .into_iter()
+        .map(|reserve| {
+            let amount = reserve.amount.multiply_ratio(lp_amount, total_share);
+            Asset::new(reserve.info, amount)
+        })
+        .collect()
+}
+
+fn lp_entries(
+    deps: Deps,
+    adapter: &DexAdapter,
+    account: String,
+    start_after: Option<(DexName, AssetEntry)>,
+    limit: Option<u8>,
+) -> DexResult<Binary> {
+    let account = deps.api.addr_validate(&account)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let min = start_after
+        .as_ref()
+        .map(|(dex, lp_token)| Bound::exclusive((dex.as_str(), lp_token)));
+
+    let entries = LP_ENTRIES
+        .sub_prefix(&account)
+        .range(deps.storage, min, None, Order::Ascending)
+        .take(limit)
+        .map(|entry| {
+            let ((dex, lp_token), entry) = entry?;
+            lp_entry_info(deps, adapter, &account, dex, lp_token, entry)
+        })
+        .collect::<DexResult<_>>()?;
+    to_binary(&LpEntriesResponse { entries }).map_err(Into::into)
+}
+
+/// Value the LP entry in its second pool asset at the current pool price
+fn lp_entry_info(
+    deps: Deps,
+    adapter: &DexAdapter,
+    account: &Addr,
+    dex: DexName,
+    lp_token: AssetEntry,
+    entry: LpEntry,
+) -> DexResult<LpEntryInfo> {
+    let mut info = LpEntryInfo {
+        dex,
+        lp_token,
+        entry,
+        current_value: None,
+        hodl_value: None,
+        impermanent_loss: None,
+    };
+    let ([x, y], Ok(exchange)) = (info.entry.deposits.as_slice(), resolve_exchange(&info.dex))
+    else {
+        return Ok(info);
+    };
+    if info.entry.lp_amount.is_zero() {
+        return Ok(info);
+    }
+    let ans = adapter.name_service(deps);
+    let pool = exchange.pair_address(deps, ans.host(), (x.name.clone(), y.name.clone()))?;
+    let (reserves, total_share) = match exchange.pool_reserves(deps, pool) {
+        Err(DexError::NotImplemented(_)) => return Ok(info),
+        reserves => reserves?,
+    };
+    let reserve_x = reserve_of(&reserves, &ans.query(&x.name)?)?;
+    let reserve_y = reserve_of(&reserves, &ans.query(&y.name)?)?;
+    if total_share.is_zero() || reserve_x.is_zero() {
+        return Ok(info);
+    }
+
+    // LP tokens received outside of the adapter aren't part of the entry
+    let balance = ans
+        .query(&info.lp_token)?
+        .query_balance(&deps.querier, account)?;
+    let lp_amount = info.entry.lp_amount.min(balance);
+    let current_value = reserve_x
+        .multiply_ratio(lp_amount, total_share)
+        .multiply_ratio(reserve_y, reserve_x)
+        + reserve_y.multiply_ratio(lp_amount, total_share);
+    let hodl_value = x.amount.multiply_ratio(reserve_y, reserve_x) + y.amount;
+    let impermanent_loss = if hodl_value.is_zero() {
+        Decimal::zero()
+    } else {
+        Decimal::from_ratio(hodl_value.saturating_sub(current_value), hodl_value)
+    };
+
+    info.current_value = Some(current_value);
+    info.hodl_value = Some(hodl_value);
+    info.impermanent_loss = Some(impermanent_loss);
+    Ok(info)
+}
+
+fn positions(
+    deps: Deps,
+    env: Env,
+    adapter: &DexAdapter,
+    account: String,
+    start_after: Option<AssetEntry>,
+    limit: Option<u8>,
+) -> DexResult<Binary> {
+    let account = deps.api.addr_validate(&account)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let ans = adapter.name_service(deps);
+
+    let mut positions = vec![];
+    let mut scanned = 0;
+    let mut next_start_after = start_after;
+    // page over the assets of the ANS until enough positions are found or enough assets are scanned
+    'scan: while positions.len() < limit && scanned < MAX_SCANNED_ASSETS {
+        let page_size = MAX_LIMIT.min((MAX_SCANNED_ASSETS - scanned) as u8);
+        let AssetListResponse { assets } = deps.querier.query_wasm_smart(
+            &ans.host().address,
+            &AnsHostQueryMsg::AssetList {
+                filter: None,
+                start_after: next_start_after.as_ref().map(ToString::to_string),
+                limit: Some(page_size),
+            },
+        )?;
+        let end_reached = assets.len() < page_size as usize;
+
+        for (lp_token, info) in assets {
+            scanned += 1;
+            next_start_after = Some(lp_token.clone());
+            let Ok(LpToken {
+                dex,
+                assets: pair_assets,
+            }) = AnsEntryConvertor::new(lp_token.clone()).lp_token()
+            else {
+                continue;
+            };
+            // only dexes of this chain
+            if !matches!(is_over_ibc(env.clone(), &dex), Ok((_, false))) {
+                continue;
+            }
+            let Ok(exchange) = resolve_exchange(&dex) else {
+                continue;
+            };
+            let balance = info.query_balance(&deps.querier, &account)?;
+            if balance.is_zero() {
+                continue;
+            }
+            let (assets, share) =
+                position_underlying(deps, adapter, exchange, pair_assets, balance)?;
+            positions.push(Position {
+                dex,
+                lp_token,
+                balance,
+                assets,
+                share,
+            });
+            if positions.len() == limit {
+                break 'scan;
+            }
+        }
+        if end_reached {
+            next_start_after = None;
+            break;
+        }
+    }
+    to_binary(&PositionsResponse {
+        positions,
+        next_start_after,
+    })
+    .map_err(Into::into)
+}
+
+/// Underlying assets and pool share of LP tokens, if the dex exposes the reserves of its pools
+fn position_underlying(
+    deps: Deps,
+    adapter: &DexAdapter,
+    exchange: &dyn DexCommand,
+    pair_assets: Vec<AssetEntry>,
+    balance: Uint128,
+) -> DexResult<(Vec<AnsAsset>, Option<Decimal>)> {
+    if pair_assets.len() < 2 {
+        return Ok((vec![], None));
+    }
+    let ans = adapter.name_service(deps);
+    let pool = exchange.pair_address(
+        deps,
+        ans.host(),
+        (pair_assets[0].clone(), pair_assets[1].clone()),
+    )?;
+    let (reserves, total_share) = match exchange.pool_reserves(deps, pool) {
+        Err(DexError::NotImplemented(_)) => return Ok((vec![], None)),
+        reserves => reserves?,
+    };
+    if total_share.is_zero() {
+        return Ok((vec![], None));
+    }
+    let underlying = lp_underlying(reserves, total_share, balance);
+    let assets = underlying_entries(deps, adapter, pair_assets, &underlying)?;
+    Ok((assets, Some(Decimal::from_ratio(balance, total_share))))
+}
+
+/// Amounts of the underlying assets per ANS entry of the pool assets
+fn underlying_entries(
+    deps: Deps,
+    adapter: &DexAdapter,
+    pair_assets: Vec<AssetEntry>,
+    underlying: &[Asset],
+) -> DexResult<Vec<AnsAsset>> {
+    let ans = adapter.name_service(deps);
+    let mut assets = vec![];
+    for entry in pair_assets {
+        let info = ans.query(&entry)?;
+        let amount = underlying
+            .iter()
+            .find(|asset| asset.info == info)
+            .map(|asset| asset.amount)
+            .unwrap_or_default();
+        assets.push(AnsAsset::new(entry, amount));
+    }
+    Ok(assets)
+}
+
+fn lp_value(
+    deps: Deps,
+    env: Env,
+    adapter: &DexAdapter,
+    dex: DexName,
+    mut lp_token: AssetEntry,
+    amount: Uint128,
+    quote_asset: Option<AssetEntry>,
+) -> DexResult<Binary> {
+    let (local_dex_name, is_over_ibc) = is_over_ibc(env, &dex)?;
+    if is_over_ibc {
+        return Err(DexError::ForeignDex(dex));
+    }
+    let exchange = resolve_exchange(&local_dex_name)?;
+    let ans = adapter.name_service(deps);
+
+    lp_token.format();
+    let pair_assets = AnsEntryConvertor::new(lp_token).lp_token()?.assets;
+    if pair_assets.len() < 2 {
+        return Err(DexError::TooFewAssets {});
+    }
+    let pool = exchange.pair_address(
+        deps,
+        ans.host(),
+        (pair_assets[0].clone(), pair_assets[1].clone()),
+    )?;
+    let (reserves, total_share) = exchange.pool_reserves(deps, pool)?;
+    let underlying = if total_share.is_zero() {
+        vec![]
+    } else {
+        lp_underlying(reserves, total_share, amount)
+    };
+
+    let assets = underlying_entries(deps, adapter, pair_assets, &underlying)?;
+
+    let value = match quote_asset {
+        Some(mut quote_asset) => {
+            quote_asset.format();
+            let mut value = Uint128::zero();
+            for asset in &assets {
+                value += quote_value(deps, adapter, exchange, asset, &quote_asset)?;
+            }
+            Some(AnsAsset::new(quote_asset, value))
+        }
+        None => None,
+    };
+    to_binary(&LpValueResponse { assets, value }).map_err(Into::into)
+}
+
+/// Value of the asset in the quote asset at the spot price of their pool on the dex
+fn quote_value(
+    deps: Deps,
+    adapter: &DexAdapter,
+    exchange: &dyn DexCommand,
+    asset: &AnsAsset,
+    quote_asset: &AssetEntry,
+) -> DexResult<Uint128> {
+    if &asset.name == quote_asset || asset.amount.is_zero() {
+        return Ok(asset.amount);
+    }
+    let ans = adapter.name_service(deps);
+    let pool =
+        exchange.pair_address(deps, ans.host(), (asset.name.clone(), quote_asset.clone()))?;
+    let (reserves, _) = exchange.pool_reserves(deps, pool)?;
+    let asset_reserve = reserve_of(&reserves, &ans.query(&asset.name)?)?;
+    let quote_reserve = reserve_of(&reserves, &ans.query(quote_asset)?)?;
+    if asset_reserve.is_zero() {
+        return Ok(Uint128::zero());
+    }
+    Ok(asset.amount.multiply_ratio(quote_reserve, asset_reserve))
+}
+
+/// Amount of the asset held by the pool
+fn reserve_of(reserves: &[Asset], info: &AssetInfo) -> DexResult<Uint128> {
+    reserves
+        .iter()
+        .find(|reserve| &reserve.info == info)
+        .map(|reserve| reserve.amount)
+        .ok_or_else(|| {
+            DexError::ArgumentMismatch(
+                info.to_string(),
+                reserves.iter().map(|r| r.info.to_string()).collect(),
+            )
+        })
+}
+
+fn ibc_actions(
+    deps: Deps,
+    account: String,
+    start_after: Option<u64>,
+    limit: Option<u8>,
+) -> DexResult<Binary> {
+    let account = deps.api.addr_validate(&account)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let min = start_after.map(Bound::exclusive);
+
+    let actions = IBC_ACTIONS
+        .prefix(&account)
+        .range(deps.storage, min, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<_>>()?;
+    to_binary(&IbcActionsResponse { actions }).map_err(Into::into)
+}
+
+fn open_orders(
+    deps: Deps,
+    account: String,
+    start_after: Option<u64>,
+    limit: Option<u8>,
+) -> DexResult<Binary> {
+    let account = deps.api.addr_validate(&account)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let min = start_after.map(Bound::exclusive);
+
+    let orders = orders()
+        .idx
+        .account
+        .prefix(account)
+        .range(deps.storage, min, None, Order::Ascending)
+        .take(limit)
+        .map(|entry| {
+            entry.map(|(order_id, state)| OrderInfo {
+                order_id,
+                dex: state.dex,
+                order: state.order,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+    to_binary(&OrdersResponse { orders }).map_err(Into::into)
+}
+
+fn dca_schedules_of(
+    deps: Deps,
+    account: String,
+    start_after: Option<u64>,
+    limit: Option<u8>,
+) -> DexResult<Binary> {
+    let account = deps.api.addr_validate(&account)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let min = start_after.map(Bound::exclusive);
+
+    let schedules = dca_schedules()
+        .idx
+        .account
+        .prefix(account)
+        .range(deps.storage, min, None, Order::Ascending)
+        .take(limit)
+        .map(|entry| {
+            entry.map(|(dca_id, state)| DcaInfo {
+                dca_id,
+                dex: state.dex,
+                schedule: state.schedule,
+                next_execution: state.next_execution,
+                paused: state.paused,
+                executions: state.executions,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+    to_binary(&DcaSchedulesResponse { schedules }).map_err(Into::into)
+}
+
+fn dca_history(
+    deps: Deps,
+    dca_id: u64,
+    start_after: Option<u64>,
+    limit: Option<u8>,
+) -> DexResult<Binary> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let min = start_after.map(Bound::exclusive);
+
+    let executions = DCA_HISTORY
+        .prefix(dca_id)
+        .range(deps.storage, min, None, Order::Ascending)
+        .take(limit)
+        .map(|entry| entry.map(|(_, execution)| execution))
+        .collect::<StdResult<_>>()?;
+    to_binary(&DcaHistoryResponse { executions }).map_err(Into::into)
+}
+
+fn twap_orders_of(
+    deps: Deps,
+    account: String,
+    start_after: Option<u64>,
+    limit: Option<u8>,
+) -> DexResult<Binary> {
+    let account = deps.api.addr_validate(&account)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let min = start_after.map(Bound::exclusive);
+
+    let orders = twap_orders()
+        .idx
+        .account
+        .prefix(account)
+        .range(deps.storage, min, None, Order::Ascending)
+        .take(limit)
+        .map(|entry| {
+            entry.map(|(twap_id, state)| TwapInfo {
+                twap_id,
+                completed: state.next_slice().is_zero(),
+                dex: state.dex,
+                twap: state.twap,
+                offered: state.offered,
+                expected_return: state.expected_return,
+                slices: state.slices,
+                next_execution: state.next_execution,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+    to_binary(&TwapOrdersResponse { orders }).map_err(Into::into)
+}
+
+fn remote_quote(
+    deps: Deps,
+    env: Env,
+    dex: DexName,
+    mut offer_asset: AssetEntry,
+    mut ask_asset: AssetEntry,
+) -> DexResult<Binary> {
+    offer_asset.format();
+    ask_asset.format();
+    let pairing = DexAssetPairing::new(offer_asset, ask_asset, &dex);
+    let quote = REMOTE_QUOTES.load(deps.storage, &pairing)?;
+
+    to_binary(&RemoteQuoteResponse {
+        age: env.block.time.seconds() - quote.updated_at.seconds(),
+        offer_asset: quote.offer_asset,
+        quote: quote.quote,
+        updated_at: quote.updated_at,
+    })
+    .map_err(Into::into)
+}
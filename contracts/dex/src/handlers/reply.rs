@@ -0,0 +1,106 @@
+use crate::contract::{DexAdapter, DexResult};
+use crate::handlers::execute::{execute_batch_step, offered_amounts_mut};
+use crate::state::{BATCH, FORWARD, SWAP_FEE};
+use abstract_sdk::cw_helpers::Chargeable;
+use abstract_sdk::Execution;
+use cosmwasm_std::{DepsMut, Env, Reply, Response, StdError};
+use cw_asset::Asset;
+
+/// Take the output of the executed batch step as input of the next step and execute it
+pub fn batch_step_reply(
+    deps: DepsMut,
+    env: Env,
+    mut adapter: DexAdapter,
+    _reply: Reply,
+) -> DexResult {
+    let mut batch = BATCH.load(deps.storage)?;
+    // replies are not executed on behalf of the account
+    adapter.target_account = Some(batch.account.clone());
+
+    let received = batch
+        .balances
+        .iter()
+        .map(|(asset, info, balance_before)| {
+            let balance = info.query_balance(&deps.querier, &batch.account.proxy)?;
+            Ok((asset.clone(), balance.saturating_sub(*balance_before)))
+        })
+        .collect::<DexResult<Vec<_>>>()?;
+
+    let (_, next_action) = &mut batch.steps[0];
+    for (asset, amount) in offered_amounts_mut(next_action) {
+        if !amount.is_zero() {
+            continue;
+        }
+        if let Some((_, output)) = received.iter().find(|(received, _)| received == asset) {
+            *amount = *output;
+        }
+    }
+    BATCH.save(deps.storage, &batch)?;
+
+    execute_batch_step(deps, &env, &adapter)
+}
+
+/// Charge the adapter fee on the output of the executed action and transfer the outputs to its recipient
+pub fn forward_reply(
+    deps: DepsMut,
+    _env: Env,
+    mut adapter: DexAdapter,
+    _reply: Reply,
+) -> DexResult {
+    let forward = FORWARD.load(deps.storage)?;
+    FORWARD.remove(deps.storage);
+    // replies are not executed on behalf of the account
+    adapter.target_account = Some(forward.account.clone());
+
+    let mut transfers = vec![];
+    let mut response = Response::new();
+    for (info, balance_before) in forward.balances {
+        let balance = info.query_balance(&deps.querier, &forward.account.proxy)?;
+        let mut output = Asset::new(info, balance.saturating_sub(balance_before));
+        if forward.fee_asset.as_ref() == Some(&output.info) {
+            let fee = SWAP_FEE.load(deps.storage)?;
+            let output_before_fee = output.amount;
+            if let Some(fee_msg) = output.charge_usage_fee(fee)? {
+                transfers.push(fee_msg.into());
+                response = response
+                    .add_attribute("usage_fee", (output_before_fee - output.amount).to_string());
+            }
+        }
+        if let Some(recipient) = &forward.recipient {
+            if !output.amount.is_zero() {
+                transfers.push(output.transfer_msg(recipient)?.into());
+            }
+        }
+    }
+    if transfers.is_empty() {
+        return Ok(response);
+    }
+    if let Some(recipient) = forward.recipient {
+        response = response.add_attribute("recipient", recipient);
+    }
+
+    let proxy_msg = adapter.executor(deps.as_ref()).execute(transfers)?;
+    Ok(response.add_message(proxy_msg))
+}
+
+/// Report the address of the pool created by the action so it can be registered in the ANS
+pub fn create_pool_reply(
+    _deps: DepsMut,
+    _env: Env,
+    _adapter: DexAdapter,
+    reply: Reply,
+) -> DexResult {
+    let result = reply.result.into_result().map_err(StdError::generic_err)?;
+    // the pool is the first contract instantiated by the factory, before its LP token
+    let pool = result
+        .events
+        .iter()
+        .filter(|event| event.ty == "instantiate")
+        .flat_map(|event| &event.attributes)
+        .find(|attribute| attribute.key == "_contract_address");
+    let response = Response::new();
+    Ok(match pool {
+        Some(pool) => response.add_attribute("pool", &pool.value),
+        None => response,
+    })
+}
@@ -14,6 +14,8 @@ pub use abstract_dex_adapter_traits::msg;
 
 #[cfg(any(feature = "juno", feature = "osmosis"))]
 pub mod host_exchange {
+    /// Payload of the actions received by the host, see [`HostDexAction::into_action`]
+    pub use abstract_dex_adapter_traits::msg::HostDexAction;
     pub use abstract_osmosis_adapter::dex::Osmosis;
 }
 
@@ -40,11 +42,14 @@ pub mod interface {
 
     impl<Chain: CwEnv> Uploadable for DexAdapter<Chain> {
         fn wrapper(&self) -> <Mock as TxHandler>::ContractSource {
-            Box::new(ContractWrapper::new_with_empty(
-                crate::contract::execute,
-                crate::contract::instantiate,
-                crate::contract::query,
-            ))
+            Box::new(
+                ContractWrapper::new_with_empty(
+                    crate::contract::execute,
+                    crate::contract::instantiate,
+                    crate::contract::query,
+                )
+                .with_reply(crate::contract::reply),
+            )
         }
         fn wasm(&self) -> WasmPath {
             artifacts_dir_from_workspace!()
@@ -74,7 +79,11 @@ pub mod interface {
                         ask_asset,
                         max_spread: Some(Decimal::percent(30)),
                         belief_price: None,
+                        recipient: None,
+                        fee_mode: FeeMode::OfferAsset,
                     },
+                    return_proceeds: false,
+                    deadline: None,
                 },
             });
             manager.execute_on_module(EXCHANGE, swap_msg)?;
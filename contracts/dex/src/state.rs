@@ -1,5 +1,329 @@
-use cw_storage_plus::Item;
+use abstract_core::objects::AccountBase;
+use cosmwasm_std::{Addr, Coin, Decimal, Decimal256, Uint128};
+use cw_asset::AssetInfo;
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
+use cw_utils::Expiration;
 
 use abstract_core::objects::fee::UsageFee;
+use abstract_core::objects::{AssetEntry, DexAssetPairing};
+use cosmwasm_schema::cw_serde;
+
+use crate::msg::{
+    AssetLists, DcaExecution, DcaSchedule, DexAction, DexName, IbcActionInfo, LpEntry, OfferAsset,
+    OracleConfig, Order, RemoteQuote, SpendLimit, TradeStats, TwapOrder,
+};
 
 pub const SWAP_FEE: Item<UsageFee> = Item::new("swap_fee");
+
+/// Swap statistics per (account proxy, dex, offer asset)
+pub const ACCOUNT_STATS: Map<(&Addr, &str, &AssetEntry), TradeStats> = Map::new("account_stats");
+
+pub struct PairStatsIndexes<'a> {
+    pub swaps: MultiIndex<'a, u64, TradeStats, &'a DexAssetPairing>,
+}
+
+impl<'a> IndexList<TradeStats> for PairStatsIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<TradeStats>> + '_> {
+        let v: Vec<&dyn Index<TradeStats>> = vec![&self.swaps];
+        Box::new(v.into_iter())
+    }
+}
+
+/// Swap statistics per (offer asset, ask asset, dex), indexed by the number of swaps
+pub fn pair_stats<'a>() -> IndexedMap<'a, &'a DexAssetPairing, TradeStats, PairStatsIndexes<'a>> {
+    let indexes = PairStatsIndexes {
+        swaps: MultiIndex::new(|_pk, stats| stats.swaps, "pair_stats", "pair_stats__swaps"),
+    };
+    IndexedMap::new("pair_stats", indexes)
+}
+
+/// Maximum price impact of the swaps per account proxy
+pub const MAX_PRICE_IMPACT: Map<&Addr, Decimal> = Map::new("max_price_impact");
+
+/// Oracle-deviation protection of the swaps per account proxy
+pub const ORACLE_CONFIG: Map<&Addr, OracleConfig> = Map::new("oracle_config");
+
+/// Assets that the actions may use per account proxy
+pub const ASSET_LISTS: Map<&Addr, AssetLists> = Map::new("asset_lists");
+
+/// Liquidity provided through the adapter per (account proxy, dex, LP token)
+pub const LP_ENTRIES: Map<(&Addr, &str, &AssetEntry), LpEntry> = Map::new("lp_entries");
+
+/// Spend limits per (account proxy, asset)
+pub const SPEND_LIMITS: Map<(&Addr, &AssetEntry), SpendState> = Map::new("spend_limits");
+
+#[cw_serde]
+pub struct SpendState {
+    pub limit: SpendLimit,
+    /// Start of the window that `spent` was spent in, in seconds.
+    /// Windows follow each other from the time the limit was set.
+    pub window_start: u64,
+    /// Amount spent since the start of the window
+    pub spent: Uint128,
+}
+
+impl SpendState {
+    /// End of the current window, `None` if it has ended at the time
+    pub fn resets_at(&self, time: u64) -> Option<u64> {
+        let end = self.window_start.saturating_add(self.limit.window);
+        (self.spent > Uint128::zero() && time < end).then_some(end)
+    }
+
+    /// Start of the window running at the time
+    pub fn window_start_at(&self, time: u64) -> u64 {
+        if self.limit.window == 0 {
+            return time;
+        }
+        let elapsed = time.saturating_sub(self.window_start);
+        self.window_start + elapsed - elapsed % self.limit.window
+    }
+
+    /// Amount spent in the window running at the time
+    pub fn spent_at(&self, time: u64) -> Uint128 {
+        match self.resets_at(time) {
+            Some(_) => self.spent,
+            None => Uint128::zero(),
+        }
+    }
+
+    /// Amount that can still be spent in the window running at the time
+    pub fn remaining_at(&self, time: u64) -> Uint128 {
+        self.limit.amount.saturating_sub(self.spent_at(time))
+    }
+}
+
+#[cw_serde]
+pub struct PriceObservation {
+    /// Time of the observation in seconds
+    pub time: u64,
+    /// Spot price of the pool, in quote asset per base asset
+    pub price: Decimal,
+    /// Sum of the prices weighted by the seconds they were observed for, up to this observation
+    pub cumulative: Decimal256,
+}
+
+impl PriceObservation {
+    /// Cumulative price at a later time, assuming the price didn't change
+    pub fn cumulative_at(&self, time: u64) -> Decimal256 {
+        self.cumulative
+            + Decimal256::from(self.price) * Decimal256::from_ratio(time - self.time, 1u64)
+    }
+}
+
+/// Spot prices observed when swapping through the adapter per ((base asset, quote asset, dex), time).
+/// Only recorded for the pairings in [`TWAP_PAIRS`] and pruned after the longest averaging window.
+pub const PRICE_HISTORY: Map<(&DexAssetPairing, u64), PriceObservation> = Map::new("price_history");
+/// Number of oracle configs with a time-weighted average price source per (base asset, quote asset, dex)
+pub const TWAP_PAIRS: Map<&DexAssetPairing, u32> = Map::new("twap_pairs");
+
+/// Last id assigned to an IBC action
+pub const IBC_ACTION_SEQUENCE: Item<u64> = Item::new("ibc_action_sequence");
+/// IBC actions per (account proxy, action id)
+pub const IBC_ACTIONS: Map<(&Addr, u64), IbcActionInfo> = Map::new("ibc_actions");
+
+#[cw_serde]
+pub struct PendingIbcAction {
+    /// Account that sent the action
+    pub account: AccountBase,
+    /// Contract that requested the action and receives its callback
+    pub callback_receiver: Option<Addr>,
+    /// Whether the output of the action, or its input if it fails, is transferred back to the account
+    pub return_proceeds: bool,
+    /// Balances of the remote account before the action was sent, only requested when its proceeds are returned
+    #[serde(default)]
+    pub balances_before: Vec<Coin>,
+    /// Deadline that the host checks when it receives the action
+    #[serde(default)]
+    pub deadline: Option<Expiration>,
+}
+
+/// IBC actions that are waiting for their callback per action id, which is part of the callback id
+pub const PENDING_IBC_ACTIONS: Map<u64, PendingIbcAction> = Map::new("pending_ibc_callbacks");
+
+#[cw_serde]
+pub struct PendingReturn {
+    /// Account that sent the action
+    pub account: AccountBase,
+    /// Chain that executed the action
+    pub host_chain: String,
+    /// Assets that are transferred back from the remote account
+    pub assets: Vec<AssetEntry>,
+    /// Balances of the remote account before the action, only the amounts above them are transferred back
+    pub balances_before: Vec<Coin>,
+}
+
+/// Returns of IBC actions that are waiting for the balances of the remote account, per action id
+pub const PENDING_RETURNS: Map<u64, PendingReturn> = Map::new("pending_returns");
+
+/// Latest quote per (offer asset, ask asset, remote dex)
+pub const REMOTE_QUOTES: Map<&DexAssetPairing, RemoteQuote> = Map::new("remote_quotes");
+/// Last id assigned to a remote quote request
+pub const REMOTE_QUOTE_SEQUENCE: Item<u64> = Item::new("remote_quote_sequence");
+
+#[cw_serde]
+pub struct PendingQuote {
+    /// Account that requested the quote
+    pub account: AccountBase,
+    pub dex: DexName,
+    pub offer_asset: OfferAsset,
+    pub ask_asset: AssetEntry,
+}
+
+/// Quote requests that are waiting for their callback per request id, which is part of the callback id
+pub const PENDING_REMOTE_QUOTES: Map<u64, PendingQuote> = Map::new("pending_quote_callbacks");
+
+#[cw_serde]
+pub struct BatchState {
+    /// Account that executes the batch
+    pub account: AccountBase,
+    /// Actions that remain to be executed
+    pub steps: Vec<(DexName, DexAction)>,
+    /// Balances of the account before the step that is being executed, for the assets that the next step takes from it
+    pub balances: Vec<(AssetEntry, AssetInfo, Uint128)>,
+}
+
+/// Batch that is being executed, only set while its steps are executed
+pub const BATCH: Item<BatchState> = Item::new("batch");
+
+#[cw_serde]
+pub struct ForwardState {
+    /// Account that executes the action
+    pub account: AccountBase,
+    /// Receiver of the outputs of the action, they stay on the account if not set
+    pub recipient: Option<Addr>,
+    /// Balances of the account before the action, for the outputs of the action
+    pub balances: Vec<(AssetInfo, Uint128)>,
+    /// Output of the action that the adapter fee is charged on
+    #[serde(default)]
+    pub fee_asset: Option<AssetInfo>,
+}
+
+/// Action whose outputs are charged the adapter fee or transferred to its recipient, only set while the action is executed
+pub const FORWARD: Item<ForwardState> = Item::new("forward");
+
+#[cw_serde]
+pub struct OrderState {
+    /// Account that placed the order, the order is executed on its behalf
+    pub account: AccountBase,
+    pub dex: DexName,
+    pub order: Order,
+}
+
+pub struct OrderIndexes<'a> {
+    pub account: MultiIndex<'a, Addr, OrderState, u64>,
+}
+
+impl<'a> IndexList<OrderState> for OrderIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<OrderState>> + '_> {
+        let v: Vec<&dyn Index<OrderState>> = vec![&self.account];
+        Box::new(v.into_iter())
+    }
+}
+
+/// Open orders per order id, indexed by the proxy of their account
+pub fn orders<'a>() -> IndexedMap<'a, u64, OrderState, OrderIndexes<'a>> {
+    let indexes = OrderIndexes {
+        account: MultiIndex::new(
+            |_pk, state| state.account.proxy.clone(),
+            "orders",
+            "orders__account",
+        ),
+    };
+    IndexedMap::new("orders", indexes)
+}
+
+/// Id of the last placed order
+pub const ORDER_SEQUENCE: Item<u64> = Item::new("order_sequence");
+
+#[cw_serde]
+pub struct DcaState {
+    /// Account that created the schedule, the swaps are executed on its behalf
+    pub account: AccountBase,
+    pub dex: DexName,
+    pub schedule: DcaSchedule,
+    /// Time in seconds from which the next swap can be executed
+    pub next_execution: u64,
+    pub paused: bool,
+    /// Number of executed swaps
+    pub executions: u64,
+}
+
+pub struct DcaIndexes<'a> {
+    pub account: MultiIndex<'a, Addr, DcaState, u64>,
+}
+
+impl<'a> IndexList<DcaState> for DcaIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<DcaState>> + '_> {
+        let v: Vec<&dyn Index<DcaState>> = vec![&self.account];
+        Box::new(v.into_iter())
+    }
+}
+
+/// Dollar-cost-averaging schedules per schedule id, indexed by the proxy of their account
+pub fn dca_schedules<'a>() -> IndexedMap<'a, u64, DcaState, DcaIndexes<'a>> {
+    let indexes = DcaIndexes {
+        account: MultiIndex::new(
+            |_pk, state| state.account.proxy.clone(),
+            "dca_schedules",
+            "dca_schedules__account",
+        ),
+    };
+    IndexedMap::new("dca_schedules", indexes)
+}
+
+/// Id of the last created schedule
+pub const DCA_SEQUENCE: Item<u64> = Item::new("dca_sequence");
+
+/// Executed swaps per (schedule id, execution time)
+pub const DCA_HISTORY: Map<(u64, u64), DcaExecution> = Map::new("dca_history");
+
+#[cw_serde]
+pub struct TwapState {
+    /// Account that placed the order, the slices are executed on its behalf
+    pub account: AccountBase,
+    pub dex: DexName,
+    pub twap: TwapOrder,
+    /// Amount of the offer asset swapped so far
+    pub offered: Uint128,
+    /// Sum of the returns of the executed slices, simulated before their execution
+    pub expected_return: Uint128,
+    /// Number of executed slices
+    pub slices: u64,
+    /// Time in seconds from which the next slice can be executed
+    pub next_execution: u64,
+}
+
+impl TwapState {
+    /// Amount offered by the next slice, zero once the total amount is swapped
+    pub fn next_slice(&self) -> Uint128 {
+        let remaining = self.twap.offer_asset.amount - self.offered;
+        remaining.min(self.twap.slice_amount)
+    }
+}
+
+pub struct TwapIndexes<'a> {
+    pub account: MultiIndex<'a, Addr, TwapState, u64>,
+}
+
+impl<'a> IndexList<TwapState> for TwapIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<TwapState>> + '_> {
+        let v: Vec<&dyn Index<TwapState>> = vec![&self.account];
+        Box::new(v.into_iter())
+    }
+}
+
+/// Time-sliced orders per order id, indexed by the proxy of their account.
+/// Completed orders are kept until they're cancelled so that their progress can be queried.
+pub fn twap_orders<'a>() -> IndexedMap<'a, u64, TwapState, TwapIndexes<'a>> {
+    let indexes = TwapIndexes {
+        account: MultiIndex::new(
+            |_pk, state| state.account.proxy.clone(),
+            "twap_orders",
+            "twap_orders__account",
+        ),
+    };
+    IndexedMap::new("twap_orders", indexes)
+}
+
+/// Id of the last placed time-sliced order
+pub const TWAP_SEQUENCE: Item<u64> = Item::new("twap_sequence");
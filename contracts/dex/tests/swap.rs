@@ -1,5 +1,13 @@
+use abstract_core::adapter::AdapterRequestMsg;
 use abstract_dex_adapter::contract::CONTRACT_VERSION;
-use abstract_dex_adapter::msg::DexInstantiateMsg;
+use abstract_dex_adapter::msg::{
+    AccountStatsResponse, AssetLists, AssetListsResponse, DcaHistoryResponse, DcaSchedule,
+    DcaSchedulesResponse, DexAction, DexExecuteMsg, DexInstantiateMsg, DexKeeperMsg, DexQueryMsg,
+    DryRunResponse, ExecuteMsg, FeeMode, LpEntriesResponse, MaxPriceImpactResponse, OfferAsset,
+    OracleConfig, OracleConfigResponse, Order, OrderTrigger, OrdersResponse, PositionsResponse,
+    PriceSource, QueryMsg, SimulateSwapResponse, SpendLimit, SpendLimitsResponse, TopPairsResponse,
+    TwapOrder, TwapOrdersResponse,
+};
 use abstract_dex_adapter::EXCHANGE;
 use abstract_interface::AdapterDeployer;
 use cw20::msg::Cw20ExecuteMsgFns;
@@ -7,11 +15,13 @@ use cw20_base::msg::QueryMsgFns;
 use cw_orch::deploy::Deploy;
 mod common;
 
+use abstract_core::objects::{AssetEntry, DexAssetPairing};
 use abstract_dex_adapter::interface::DexAdapter;
 use abstract_interface::Abstract;
 use abstract_interface::AbstractAccount;
 use common::create_default_account;
 use cosmwasm_std::{coin, Addr, Decimal, Empty};
+use cw_asset::Asset;
 
 use cw_orch::prelude::*;
 use speculoos::*;
@@ -136,3 +146,633 @@ fn swap_raw() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn swap_updates_stats() -> anyhow::Result<()> {
+    let (_, _, dex_adapter, os) = setup_mock()?;
+    let proxy_addr = os.proxy.address()?;
+
+    // swap 100 EUR to USD twice
+    dex_adapter.swap((EUR, 100), USD, WYNDEX.into())?;
+    dex_adapter.swap((EUR, 100), USD, WYNDEX.into())?;
+
+    let account_stats: AccountStatsResponse =
+        dex_adapter.query(&QueryMsg::Module(DexQueryMsg::AccountStats {
+            account: proxy_addr.to_string(),
+            start_after: None,
+            limit: None,
+        }))?;
+    assert_that!(account_stats.stats).has_length(1);
+    let (dex, asset, stats) = &account_stats.stats[0];
+    assert_that!(dex.as_str()).is_equal_to(WYNDEX_WITHOUT_CHAIN);
+    assert_that!(asset).is_equal_to(&AssetEntry::new(EUR));
+    assert_that!(stats.volume.u128()).is_equal_to(200);
+    assert_that!(stats.fees.u128()).is_equal_to(2);
+    assert_that!(stats.swaps).is_equal_to(2);
+
+    let top_pairs: TopPairsResponse =
+        dex_adapter.query(&QueryMsg::Module(DexQueryMsg::TopPairs {
+            start_after: None,
+            limit: None,
+        }))?;
+    assert_that!(top_pairs.pairs).has_length(1);
+    assert_that!(top_pairs.pairs[0].0).is_equal_to(DexAssetPairing::new(
+        AssetEntry::new(EUR),
+        AssetEntry::new(USD),
+        WYNDEX_WITHOUT_CHAIN,
+    ));
+    assert_that!(top_pairs.pairs[0].1.swaps).is_equal_to(2);
+
+    let (pairing, stats) = top_pairs.pairs[0].clone();
+    let next_page: TopPairsResponse =
+        dex_adapter.query(&QueryMsg::Module(DexQueryMsg::TopPairs {
+            start_after: Some((stats.swaps, pairing)),
+            limit: None,
+        }))?;
+    assert_that!(next_page.pairs).is_empty();
+
+    Ok(())
+}
+
+#[test]
+fn max_price_impact() -> anyhow::Result<()> {
+    let (chain, _, dex_adapter, os) = setup_mock()?;
+    let proxy_addr = os.proxy.address()?;
+
+    let set_max_price_impact = |max_price_impact: Option<Decimal>| {
+        os.manager.execute_on_module(
+            EXCHANGE,
+            ExecuteMsg::Module(AdapterRequestMsg {
+                proxy_address: None,
+                request: DexExecuteMsg::SetMaxPriceImpact { max_price_impact },
+            }),
+        )
+    };
+    let query_max_price_impact = || -> anyhow::Result<Option<Decimal>> {
+        let response: MaxPriceImpactResponse =
+            dex_adapter.query(&QueryMsg::Module(DexQueryMsg::MaxPriceImpact {
+                account: proxy_addr.to_string(),
+            }))?;
+        Ok(response.max_price_impact)
+    };
+
+    // more than 100% is rejected
+    assert_that!(set_max_price_impact(Some(Decimal::percent(101)))).is_err();
+
+    set_max_price_impact(Some(Decimal::percent(10)))?;
+    assert_that!(query_max_price_impact()?).is_equal_to(Some(Decimal::percent(10)));
+
+    // small swaps stay below the maximum
+    dex_adapter.swap((EUR, 100), USD, WYNDEX.into())?;
+    let usd_balance = chain.query_balance(&proxy_addr, USD)?;
+    assert_that!(usd_balance.u128()).is_equal_to(98);
+
+    set_max_price_impact(None)?;
+    assert_that!(query_max_price_impact()?).is_equal_to(None);
+
+    Ok(())
+}
+
+#[test]
+fn oracle_deviation() -> anyhow::Result<()> {
+    let (_, _, dex_adapter, os) = setup_mock()?;
+    let proxy_addr = os.proxy.address()?;
+
+    let set_oracle_config = |window: u64| {
+        let twap = PriceSource::Twap {
+            dex: WYNDEX_WITHOUT_CHAIN.to_string(),
+            quote: AssetEntry::new(USD),
+            window,
+        };
+        os.manager.execute_on_module(
+            EXCHANGE,
+            ExecuteMsg::Module(AdapterRequestMsg {
+                proxy_address: None,
+                request: DexExecuteMsg::SetOracleConfig {
+                    config: Some(OracleConfig {
+                        sources: vec![
+                            (AssetEntry::new(EUR), twap.clone()),
+                            (AssetEntry::new(USD), twap),
+                        ],
+                        max_deviation: Decimal::percent(10),
+                    }),
+                },
+            }),
+        )
+    };
+
+    // record the spot price of the pool
+    dex_adapter.swap((EUR, 100), USD, WYNDEX.into())?;
+
+    // there is no price history for an hour
+    set_oracle_config(3600)?;
+    assert_that!(dex_adapter.swap((EUR, 100), USD, WYNDEX.into())).is_err();
+
+    // the swap is close to the last spot price
+    set_oracle_config(0)?;
+    dex_adapter.swap((EUR, 100), USD, WYNDEX.into())?;
+
+    let response: OracleConfigResponse =
+        dex_adapter.query(&QueryMsg::Module(DexQueryMsg::OracleConfig {
+            account: proxy_addr.to_string(),
+        }))?;
+    assert_that!(response.config.map(|config| config.max_deviation))
+        .is_equal_to(Some(Decimal::percent(10)));
+
+    Ok(())
+}
+
+#[test]
+fn asset_lists() -> anyhow::Result<()> {
+    let (chain, _, dex_adapter, os) = setup_mock()?;
+    let proxy_addr = os.proxy.address()?;
+
+    let set_asset_lists = |lists: Option<AssetLists>| {
+        os.manager.execute_on_module(
+            EXCHANGE,
+            ExecuteMsg::Module(AdapterRequestMsg {
+                proxy_address: None,
+                request: DexExecuteMsg::SetAssetLists { lists },
+            }),
+        )
+    };
+
+    // denied assets can't be offered
+    set_asset_lists(Some(AssetLists {
+        allowed: None,
+        denied: vec![AssetEntry::new(EUR)],
+    }))?;
+    assert_that!(dex_adapter.swap((EUR, 100), USD, WYNDEX.into())).is_err();
+
+    // assets outside of the allow-list can't be asked
+    set_asset_lists(Some(AssetLists {
+        allowed: Some(vec![AssetEntry::new(EUR)]),
+        denied: vec![],
+    }))?;
+    assert_that!(dex_adapter.swap((EUR, 100), USD, WYNDEX.into())).is_err();
+
+    let response: AssetListsResponse =
+        dex_adapter.query(&QueryMsg::Module(DexQueryMsg::AssetLists {
+            account: proxy_addr.to_string(),
+        }))?;
+    assert_that!(response.lists.and_then(|lists| lists.allowed))
+        .is_equal_to(Some(vec![AssetEntry::new(EUR)]));
+
+    set_asset_lists(None)?;
+    dex_adapter.swap((EUR, 100), USD, WYNDEX.into())?;
+    let usd_balance = chain.query_balance(&proxy_addr, USD)?;
+    assert_that!(usd_balance.u128()).is_equal_to(98);
+
+    Ok(())
+}
+
+#[test]
+fn spend_limits() -> anyhow::Result<()> {
+    let (chain, _, dex_adapter, os) = setup_mock()?;
+    let proxy_addr = os.proxy.address()?;
+
+    let set_spend_limit = |window: u64| {
+        os.manager.execute_on_module(
+            EXCHANGE,
+            ExecuteMsg::Module(AdapterRequestMsg {
+                proxy_address: None,
+                request: DexExecuteMsg::SetSpendLimit {
+                    asset: AssetEntry::new(EUR),
+                    limit: Some(SpendLimit {
+                        amount: 150u128.into(),
+                        window,
+                    }),
+                },
+            }),
+        )
+    };
+    // an empty window isn't a limit per window
+    assert_that!(set_spend_limit(0)).is_err();
+    set_spend_limit(86_400)?;
+
+    dex_adapter.swap((EUR, 100), USD, WYNDEX.into())?;
+    // only 50 EUR left in this window
+    assert_that!(dex_adapter.swap((EUR, 100), USD, WYNDEX.into())).is_err();
+
+    let response: SpendLimitsResponse =
+        dex_adapter.query(&QueryMsg::Module(DexQueryMsg::SpendLimits {
+            account: proxy_addr.to_string(),
+            start_after: None,
+            limit: None,
+        }))?;
+    assert_that!(response.limits).has_length(1);
+    let (asset, allowance) = &response.limits[0];
+    assert_that!(asset).is_equal_to(&AssetEntry::new(EUR));
+    assert_that!(allowance.spent.u128()).is_equal_to(100);
+    assert_that!(allowance.remaining.u128()).is_equal_to(50);
+
+    // the allowance resets after the window
+    chain.wait_seconds(86_400)?;
+    dex_adapter.swap((EUR, 100), USD, WYNDEX.into())?;
+    let eur_balance = chain.query_balance(&proxy_addr, EUR)?;
+    assert_that!(eur_balance.u128()).is_equal_to(9_800);
+
+    Ok(())
+}
+
+#[test]
+fn dry_run_swap() -> anyhow::Result<()> {
+    let (chain, _, dex_adapter, os) = setup_mock()?;
+    let proxy_addr = os.proxy.address()?;
+
+    let response: DryRunResponse = dex_adapter.query(&QueryMsg::Module(DexQueryMsg::DryRun {
+        dex: WYNDEX.into(),
+        action: DexAction::Swap {
+            offer_asset: OfferAsset::new(EUR, 100u128),
+            ask_asset: AssetEntry::new(USD),
+            max_spread: None,
+            belief_price: None,
+            recipient: None,
+            fee_mode: FeeMode::OfferAsset,
+        },
+        account: proxy_addr.to_string(),
+    }))?;
+    assert_that!(response.offered).is_equal_to(vec![Asset::native(EUR, 100u128)]);
+    assert_that!(response.received).is_equal_to(vec![Asset::native(USD, 98u128)]);
+    assert_that!(response.fees).contains(Asset::native(EUR, 1u128));
+
+    // nothing is executed
+    let eur_balance = chain.query_balance(&proxy_addr, EUR)?;
+    assert_that!(eur_balance.u128()).is_equal_to(10_000);
+
+    Ok(())
+}
+
+#[test]
+fn swap_fee_on_ask_asset() -> anyhow::Result<()> {
+    let (chain, _, dex_adapter, os) = setup_mock()?;
+    let proxy_addr = os.proxy.address()?;
+
+    let simulation: SimulateSwapResponse =
+        dex_adapter.query(&QueryMsg::Module(DexQueryMsg::SimulateSwap {
+            offer_asset: OfferAsset::new(EUR, 1_000u128),
+            ask_asset: AssetEntry::new(USD),
+            dex: Some(WYNDEX_WITHOUT_CHAIN.into()),
+            fee_mode: FeeMode::AskAsset,
+        }))?;
+    assert_that!(simulation.fee_mode).is_equal_to(FeeMode::AskAsset);
+    assert_that!(simulation.usage_fee.u128()).is_greater_than(0);
+
+    os.manager.execute_on_module(
+        EXCHANGE,
+        ExecuteMsg::Module(AdapterRequestMsg {
+            proxy_address: None,
+            request: DexExecuteMsg::Action {
+                dex: WYNDEX.into(),
+                action: DexAction::Swap {
+                    offer_asset: OfferAsset::new(EUR, 1_000u128),
+                    ask_asset: AssetEntry::new(USD),
+                    max_spread: None,
+                    belief_price: None,
+                    recipient: None,
+                    fee_mode: FeeMode::AskAsset,
+                },
+                return_proceeds: false,
+                deadline: None,
+            },
+        }),
+    )?;
+
+    // the full offer amount reaches the pool
+    let eur_balance = chain.query_balance(&proxy_addr, EUR)?;
+    assert_that!(eur_balance.u128()).is_equal_to(9_000);
+    let usd_balance = chain.query_balance(&proxy_addr, USD)?;
+    assert_that!(usd_balance).is_equal_to(simulation.return_amount);
+
+    // the fee is paid in the ask asset
+    let os0_proxy = AbstractAccount::new(chain.clone(), Some(0))
+        .proxy
+        .address()?;
+    assert_that!(chain.query_balance(&os0_proxy, EUR)?.u128()).is_equal_to(0);
+    assert_that!(chain.query_balance(&os0_proxy, USD)?).is_equal_to(simulation.usage_fee);
+
+    Ok(())
+}
+
+#[test]
+fn orders() -> anyhow::Result<()> {
+    let (chain, _, dex_adapter, os) = setup_mock()?;
+    let proxy_addr = os.proxy.address()?;
+    let keeper = Addr::unchecked("keeper");
+
+    let place_order = |trigger: OrderTrigger| -> anyhow::Result<()> {
+        os.manager.execute_on_module(
+            EXCHANGE,
+            ExecuteMsg::Module(AdapterRequestMsg {
+                proxy_address: None,
+                request: DexExecuteMsg::PlaceOrder {
+                    dex: WYNDEX.into(),
+                    order: Order {
+                        offer_asset: OfferAsset::new(EUR, 100u128),
+                        ask_asset: AssetEntry::new(USD),
+                        trigger,
+                        max_spread: Decimal::percent(10),
+                        keeper_tip: 10u128.into(),
+                    },
+                },
+            }),
+        )?;
+        Ok(())
+    };
+    let query_orders = || -> anyhow::Result<OrdersResponse> {
+        Ok(dex_adapter.query(&QueryMsg::Module(DexQueryMsg::Orders {
+            account: proxy_addr.to_string(),
+            start_after: None,
+            limit: None,
+        }))?)
+    };
+    let execute_order = |order_id: u64| {
+        dex_adapter.call_as(&keeper).execute(
+            &ExecuteMsg::Receive(DexKeeperMsg::ExecuteOrder { order_id }),
+            None,
+        )
+    };
+
+    assert_that!(place_order(OrderTrigger::TakeProfit(Decimal::zero()))).is_err();
+    // the pool price of about one triggers the take profit but not the stop loss
+    place_order(OrderTrigger::TakeProfit(Decimal::percent(50)))?;
+    place_order(OrderTrigger::StopLoss(Decimal::percent(1)))?;
+    let orders = query_orders()?.orders;
+    assert_that!(orders).has_length(2);
+    assert_that!(orders[0].order_id).is_equal_to(1);
+    assert_that!(orders[0].dex.as_str()).is_equal_to(WYNDEX_WITHOUT_CHAIN);
+
+    assert_that!(execute_order(2)).is_err();
+    execute_order(1)?;
+
+    // the keeper receives the tip and the rest is swapped
+    assert_that!(chain.query_balance(&keeper, EUR)?.u128()).is_equal_to(10);
+    assert_that!(chain.query_balance(&proxy_addr, EUR)?.u128()).is_equal_to(9_900);
+    assert_that!(chain.query_balance(&proxy_addr, USD)?.u128()).is_greater_than(0);
+    assert_that!(execute_order(1)).is_err();
+
+    os.manager.execute_on_module(
+        EXCHANGE,
+        ExecuteMsg::Module(AdapterRequestMsg {
+            proxy_address: None,
+            request: DexExecuteMsg::CancelOrder { order_id: 2 },
+        }),
+    )?;
+    assert_that!(query_orders()?.orders).is_empty();
+
+    Ok(())
+}
+
+#[test]
+fn dca() -> anyhow::Result<()> {
+    let (chain, _, dex_adapter, os) = setup_mock()?;
+    let proxy_addr = os.proxy.address()?;
+    let keeper = Addr::unchecked("keeper");
+
+    let request = |request: DexExecuteMsg| -> anyhow::Result<()> {
+        os.manager.execute_on_module(
+            EXCHANGE,
+            ExecuteMsg::Module(AdapterRequestMsg {
+                proxy_address: None,
+                request,
+            }),
+        )?;
+        Ok(())
+    };
+    let query_schedules = || -> anyhow::Result<DcaSchedulesResponse> {
+        Ok(
+            dex_adapter.query(&QueryMsg::Module(DexQueryMsg::DcaSchedules {
+                account: proxy_addr.to_string(),
+                start_after: None,
+                limit: None,
+            }))?,
+        )
+    };
+    let execute_dca = |dca_id: u64| {
+        dex_adapter.call_as(&keeper).execute(
+            &ExecuteMsg::Receive(DexKeeperMsg::ExecuteDca { dca_id }),
+            None,
+        )
+    };
+
+    request(DexExecuteMsg::CreateDca {
+        dex: WYNDEX.into(),
+        schedule: DcaSchedule {
+            offer_asset: OfferAsset::new(EUR, 100u128),
+            ask_asset: AssetEntry::new(USD),
+            interval: 3_600,
+            min_price: Decimal::percent(50),
+            max_spread: Decimal::percent(10),
+        },
+    })?;
+    let schedules = query_schedules()?.schedules;
+    assert_that!(schedules).has_length(1);
+    assert_that!(schedules[0].dca_id).is_equal_to(1);
+    assert_that!(schedules[0].dex.as_str()).is_equal_to(WYNDEX_WITHOUT_CHAIN);
+
+    // the first swap is due right away, the next one after the interval
+    execute_dca(1)?;
+    assert_that!(chain.query_balance(&proxy_addr, EUR)?.u128()).is_equal_to(9_900);
+    assert_that!(chain.query_balance(&proxy_addr, USD)?.u128()).is_greater_than(0);
+    assert_that!(execute_dca(1)).is_err();
+
+    chain.wait_seconds(3_600)?;
+    request(DexExecuteMsg::PauseDca { dca_id: 1 })?;
+    assert_that!(execute_dca(1)).is_err();
+    request(DexExecuteMsg::ResumeDca { dca_id: 1 })?;
+    execute_dca(1)?;
+    assert_that!(chain.query_balance(&proxy_addr, EUR)?.u128()).is_equal_to(9_800);
+
+    let history: DcaHistoryResponse =
+        dex_adapter.query(&QueryMsg::Module(DexQueryMsg::DcaHistory {
+            dca_id: 1,
+            start_after: None,
+            limit: None,
+        }))?;
+    assert_that!(history.executions).has_length(2);
+    assert_that!(query_schedules()?.schedules[0].executions).is_equal_to(2);
+
+    // swaps below the minimum price of the schedule are rejected
+    request(DexExecuteMsg::CreateDca {
+        dex: WYNDEX.into(),
+        schedule: DcaSchedule {
+            offer_asset: OfferAsset::new(EUR, 100u128),
+            ask_asset: AssetEntry::new(USD),
+            interval: 3_600,
+            min_price: Decimal::percent(200),
+            max_spread: Decimal::percent(10),
+        },
+    })?;
+    assert_that!(execute_dca(2)).is_err();
+    assert_that!(chain.query_balance(&proxy_addr, EUR)?.u128()).is_equal_to(9_800);
+
+    request(DexExecuteMsg::CancelDca { dca_id: 1 })?;
+    request(DexExecuteMsg::CancelDca { dca_id: 2 })?;
+    assert_that!(query_schedules()?.schedules).is_empty();
+
+    Ok(())
+}
+
+#[test]
+fn twap() -> anyhow::Result<()> {
+    let (chain, _, dex_adapter, os) = setup_mock()?;
+    let proxy_addr = os.proxy.address()?;
+    let keeper = Addr::unchecked("keeper");
+
+    let request = |request: DexExecuteMsg| -> anyhow::Result<()> {
+        os.manager.execute_on_module(
+            EXCHANGE,
+            ExecuteMsg::Module(AdapterRequestMsg {
+                proxy_address: None,
+                request,
+            }),
+        )?;
+        Ok(())
+    };
+    let query_orders = || -> anyhow::Result<TwapOrdersResponse> {
+        Ok(
+            dex_adapter.query(&QueryMsg::Module(DexQueryMsg::TwapOrders {
+                account: proxy_addr.to_string(),
+                start_after: None,
+                limit: None,
+            }))?,
+        )
+    };
+    let execute_slice = |twap_id: u64| {
+        dex_adapter.call_as(&keeper).execute(
+            &ExecuteMsg::Receive(DexKeeperMsg::ExecuteTwapSlice { twap_id }),
+            None,
+        )
+    };
+    let place_twap = |min_price: Decimal| {
+        request(DexExecuteMsg::PlaceTwap {
+            dex: WYNDEX.into(),
+            twap: TwapOrder {
+                offer_asset: OfferAsset::new(EUR, 250u128),
+                ask_asset: AssetEntry::new(USD),
+                slice_amount: 100u128.into(),
+                interval: 600,
+                min_price,
+                max_spread: Decimal::percent(10),
+            },
+        })
+    };
+
+    // no price reaches the limit of the second order
+    place_twap(Decimal::zero())?;
+    place_twap(Decimal::MAX)?;
+    assert_that!(execute_slice(2)).is_err();
+
+    execute_slice(1)?;
+    assert_that!(chain.query_balance(&proxy_addr, EUR)?.u128()).is_equal_to(9_900);
+    assert_that!(execute_slice(1)).is_err();
+
+    // the last slice offers what remains
+    chain.wait_seconds(600)?;
+    execute_slice(1)?;
+    chain.wait_seconds(600)?;
+    execute_slice(1)?;
+    assert_that!(chain.query_balance(&proxy_addr, EUR)?.u128()).is_equal_to(9_750);
+    assert_that!(chain.query_balance(&proxy_addr, USD)?.u128()).is_greater_than(0);
+
+    let orders = query_orders()?.orders;
+    assert_that!(orders).has_length(2);
+    assert_that!(orders[0].offered.u128()).is_equal_to(250);
+    assert_that!(orders[0].slices).is_equal_to(3);
+    assert_that!(orders[0].completed).is_true();
+    assert_that!(orders[1].completed).is_false();
+    chain.wait_seconds(600)?;
+    assert_that!(execute_slice(1)).is_err();
+
+    request(DexExecuteMsg::CancelTwap { twap_id: 1 })?;
+    request(DexExecuteMsg::CancelTwap { twap_id: 2 })?;
+    assert_that!(query_orders()?.orders).is_empty();
+
+    Ok(())
+}
+
+#[test]
+fn liquidity_positions() -> anyhow::Result<()> {
+    let (chain, _, dex_adapter, os) = setup_mock()?;
+    let proxy_addr = os.proxy.address()?;
+    chain.set_balance(&proxy_addr, vec![coin(10_000, EUR), coin(10_000, USD)])?;
+
+    let query_positions = || -> anyhow::Result<PositionsResponse> {
+        Ok(dex_adapter.query(&QueryMsg::Module(DexQueryMsg::Positions {
+            account: proxy_addr.to_string(),
+            start_after: None,
+            limit: None,
+        }))?)
+    };
+    assert_that!(query_positions()?.positions).is_empty();
+    // the few assets of the test ANS are scanned at once
+    assert_that!(query_positions()?.next_start_after).is_none();
+
+    os.manager.execute_on_module(
+        EXCHANGE,
+        ExecuteMsg::Module(AdapterRequestMsg {
+            proxy_address: None,
+            request: DexExecuteMsg::Action {
+                dex: WYNDEX.into(),
+                action: DexAction::ProvideLiquidity {
+                    assets: vec![
+                        OfferAsset::new(EUR, 1_000u128),
+                        OfferAsset::new(USD, 1_000u128),
+                    ],
+                    max_spread: None,
+                    recipient: None,
+                },
+                return_proceeds: false,
+                deadline: None,
+            },
+        }),
+    )?;
+
+    let positions = query_positions()?.positions;
+    assert_that!(positions).has_length(1);
+    assert_that!(positions[0].dex.as_str()).is_equal_to(WYNDEX_WITHOUT_CHAIN);
+    assert_that!(positions[0].balance.u128()).is_greater_than(0);
+
+    Ok(())
+}
+
+#[test]
+fn lp_entries() -> anyhow::Result<()> {
+    let (chain, _, dex_adapter, os) = setup_mock()?;
+    let proxy_addr = os.proxy.address()?;
+    chain.set_balance(&proxy_addr, vec![coin(10_000, EUR), coin(10_000, USD)])?;
+
+    os.manager.execute_on_module(
+        EXCHANGE,
+        ExecuteMsg::Module(AdapterRequestMsg {
+            proxy_address: None,
+            request: DexExecuteMsg::Action {
+                dex: WYNDEX.into(),
+                action: DexAction::ProvideLiquidity {
+                    assets: vec![
+                        OfferAsset::new(EUR, 1_000u128),
+                        OfferAsset::new(USD, 1_000u128),
+                    ],
+                    max_spread: None,
+                    recipient: None,
+                },
+                return_proceeds: false,
+                deadline: None,
+            },
+        }),
+    )?;
+
+    let response: LpEntriesResponse =
+        dex_adapter.query(&QueryMsg::Module(DexQueryMsg::LpEntries {
+            account: proxy_addr.to_string(),
+            start_after: None,
+            limit: None,
+        }))?;
+    assert_that!(response.entries).has_length(1);
+    let info = &response.entries[0];
+    assert_that!(info.dex.as_str()).is_equal_to(WYNDEX_WITHOUT_CHAIN);
+    assert_that!(info.entry.deposits).contains(OfferAsset::new(EUR, 1_000u128));
+    assert_that!(info.entry.entry_price).is_equal_to(Decimal::one());
+
+    Ok(())
+}
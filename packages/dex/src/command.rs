@@ -2,16 +2,17 @@ use std::error::Error;
 
 use crate::error::DexError;
 use abstract_adapter_utils::identity::Identify;
-use abstract_core::objects::{DexAssetPairing, PoolAddress, PoolReference};
+use abstract_core::objects::{DexAssetPairing, PoolAddress, PoolReference, PoolType};
 use abstract_sdk::core::objects::AssetEntry;
 use abstract_sdk::feature_objects::AnsHost;
-use cosmwasm_std::{CosmosMsg, Decimal, Deps, Uint128};
+use cosmwasm_std::{Addr, CosmosMsg, Decimal, Deps, Uint128};
 use cw_asset::{Asset, AssetInfo};
 
 pub type Return = Uint128;
 pub type Spread = Uint128;
 pub type Fee = Uint128;
 pub type FeeOnInput = bool;
+pub type TotalShare = Uint128;
 
 /// # DexCommand
 /// ensures DEX adapters support the expected functionality.
@@ -46,6 +47,22 @@ pub trait DexCommand<E: Error = DexError>: Identify {
         max_spread: Option<Decimal>,
     ) -> Result<Vec<CosmosMsg>, E>;
 
+    /// Execute a swap that sends the returned asset to the recipient
+    #[allow(clippy::too_many_arguments)]
+    fn swap_to(
+        &self,
+        _deps: Deps,
+        _pool_id: PoolAddress,
+        _offer_asset: Asset,
+        _ask_asset: AssetInfo,
+        _belief_price: Option<Decimal>,
+        _max_spread: Option<Decimal>,
+        _recipient: Addr,
+    ) -> Result<Vec<CosmosMsg>, DexError> {
+        // The adapter transfers the returned asset when the DEX can't send it to the recipient
+        Err(DexError::NotImplemented(self.name().to_string()))
+    }
+
     /// Implement your custom swap the DEX
     fn custom_swap(
         &self,
@@ -67,6 +84,19 @@ pub trait DexCommand<E: Error = DexError>: Identify {
         max_spread: Option<Decimal>,
     ) -> Result<Vec<CosmosMsg>, E>;
 
+    /// Provides liquidity on the DEX and sends the LP tokens to the recipient
+    fn provide_liquidity_to(
+        &self,
+        _deps: Deps,
+        _pool_id: PoolAddress,
+        _offer_assets: Vec<Asset>,
+        _max_spread: Option<Decimal>,
+        _recipient: Addr,
+    ) -> Result<Vec<CosmosMsg>, DexError> {
+        // The adapter transfers the LP tokens when the DEX can't send them to the recipient
+        Err(DexError::NotImplemented(self.name().to_string()))
+    }
+
     /// Provide symmetric liquidity where available depending on the DEX
     fn provide_liquidity_symmetric(
         &self,
@@ -84,6 +114,17 @@ pub trait DexCommand<E: Error = DexError>: Identify {
         lp_token: Asset,
     ) -> Result<Vec<CosmosMsg>, E>;
 
+    /// Withdraw liquidity from DEX into a single asset of the pool
+    fn withdraw_liquidity_single_asset(
+        &self,
+        _deps: Deps,
+        _pool_id: PoolAddress,
+        _lp_token: Asset,
+        _ask_asset: AssetInfo,
+    ) -> Result<Vec<CosmosMsg>, DexError> {
+        Err(DexError::NotImplemented(self.name().to_string()))
+    }
+
     /// Simulate a swap in the DEX
     fn simulate_swap(
         &self,
@@ -93,9 +134,26 @@ pub trait DexCommand<E: Error = DexError>: Identify {
         ask_asset: AssetInfo,
     ) -> Result<(Return, Spread, Fee, FeeOnInput), E>;
 
-    // fn raw_swap();
-    // fn raw_provide_liquidity();
-    // fn raw_withdraw_liquidity();
+    /// Create a pool of the assets through the factory of the DEX, depositing the amounts of the assets
+    fn create_pool(
+        &self,
+        _deps: Deps,
+        _factory: Addr,
+        _assets: Vec<Asset>,
+        _pool_type: PoolType,
+    ) -> Result<Vec<CosmosMsg>, DexError> {
+        Err(DexError::NotImplemented(self.name().to_string()))
+    }
+
+    /// Assets held by the pool and the total supply of its LP token
+    fn pool_reserves(
+        &self,
+        _deps: Deps,
+        _pool_id: PoolAddress,
+    ) -> Result<(Vec<Asset>, TotalShare), DexError> {
+        Err(DexError::NotImplemented(self.name().to_string()))
+    }
+
     // fn route_swap();
     // fn raw_route_swap();
 }
@@ -2,8 +2,9 @@ use abstract_adapter::AdapterError;
 use abstract_core::objects::DexAssetPairing;
 use abstract_core::AbstractError;
 use abstract_sdk::AbstractSdkError;
-use cosmwasm_std::StdError;
+use cosmwasm_std::{Decimal, StdError, Uint128};
 use cw_asset::AssetError;
+use cw_utils::Expiration;
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
@@ -41,6 +42,9 @@ pub enum DexError {
     #[error("Provided asset {0} not in pool with assets {1:?}.")]
     ArgumentMismatch(String, Vec<String>),
 
+    #[error("DEX {0} expects a pool with {1}, got pool address {2}")]
+    InvalidPoolAddress(String, String, String),
+
     #[error("Balancer pool not supported for dex {0}.")]
     BalancerNotSupported(String),
 
@@ -56,9 +60,141 @@ pub enum DexError {
     #[error("Message generation for IBC queries not supported.")]
     IbcMsgQuery,
 
+    #[error("DEX {0} is local to this network, use the SimulateSwap query instead.")]
+    LocalDexQuote(String),
+
+    #[error("A proxy address is required to generate messages for {0}")]
+    ProxyAddressRequired(String),
+
     #[error("Asset pairing {} not found.", asset_pairing)]
     AssetPairingNotFound { asset_pairing: DexAssetPairing },
 
     #[error("Invalid Generate Message")]
     InvalidGenerateMessage,
+
+    #[error("Sender {0} is not the owner of the account")]
+    NotAccountOwner(String),
+
+    #[error("Maximum price impact {0} is above 100%")]
+    InvalidMaxPriceImpact(Decimal),
+
+    #[error("Price impact {0} exceeds the maximum of {1} set by the account")]
+    MaxPriceImpactExceeded(Decimal, Decimal),
+
+    #[error("Custom swaps can't be simulated, they are rejected while the account guards the price of its swaps")]
+    CustomSwapPriceGuard {},
+
+    #[error("Swap price {0} deviates too far from the oracle price {1}")]
+    OracleDeviationExceeded(Decimal, Decimal),
+
+    #[error("Swaps on {0} can't be simulated over IBC, they are rejected while the account guards the price of its swaps")]
+    PriceGuardOverIbc(String),
+
+    #[error("Averaging window of {0} seconds exceeds the maximum of {1}")]
+    InvalidTwapWindow(u64, u64),
+
+    #[error("Not enough price history for a time-weighted average price of {0}")]
+    TwapUnavailable(String),
+
+    #[error("Asset {0} is not allowed by the asset lists of the account")]
+    AssetNotAllowed(String),
+
+    #[error("Spend limit of {0} exceeded, {1} remaining in the current window")]
+    SpendLimitExceeded(String, Uint128),
+
+    #[error("Window of a spend limit must be at least one second")]
+    InvalidSpendWindow {},
+
+    #[error("Pool type {0} is not supported by {1}")]
+    PoolTypeNotSupported(String, String),
+
+    #[error("Dry run of {0} is not supported")]
+    DryRunNotSupported(String),
+
+    #[error("Action deadline {0} has passed")]
+    DeadlineExpired(Expiration),
+
+    #[error("Block height {0} of a remote chain can't be checked, IBC actions only support time deadlines")]
+    HeightDeadlineOverIbc(u64),
+
+    #[error("Callback {0} is not sent by the IBC client of an account with a pending action")]
+    UnauthorizedCallback(String),
+
+    #[error("Returning proceeds is only supported for actions executed over IBC")]
+    ReturnProceedsNotOverIbc {},
+
+    #[error("Proceeds are returned when the adapter receives the callback of the action, generated messages can't return them")]
+    ReturnProceedsNotGenerated {},
+
+    #[error("ICS20 channel {0} is not open")]
+    UnknownIbcChannel(String),
+
+    #[error("Maximum amount of paired asset {0} is required to provide liquidity over IBC")]
+    MissingPairedAssetBound(String),
+
+    #[error("Raw actions can't be executed over IBC")]
+    RawActionOverIbc {},
+
+    #[error("Account {0} would be resolved by the host chain over IBC, use the address of the recipient on the host instead")]
+    AccountRecipientOverIbc(u32),
+
+    #[error("DEX {0} can't send the output of this action to a recipient, it can only be executed on its own")]
+    RecipientNotSupported(String),
+
+    #[error("Fees on the ask asset are charged in replies of the adapter, the swap on {0} can only be executed on its own")]
+    AskAssetFeeNotSupported(String),
+
+    #[error("Keeper tip {0} must be lower than the offered amount {1}")]
+    InvalidKeeperTip(Uint128, Uint128),
+
+    #[error("Order {0} is not triggered at price {1}")]
+    OrderNotTriggered(u64, Decimal),
+
+    #[error("Order {0} belongs to another account")]
+    NotOrderOwner(u64),
+
+    #[error("Interval of a dollar-cost-averaging schedule must be at least one second")]
+    InvalidDcaInterval {},
+
+    #[error("Trigger price of an order must be above zero")]
+    InvalidOrderTrigger {},
+
+    #[error("Maximum spread {0} of a stored swap must be below 100%")]
+    InvalidMaxSpread(Decimal),
+
+    #[error("Schedule {0} is not due before {1}")]
+    DcaNotDue(u64, u64),
+
+    #[error("Schedule {0} is paused")]
+    DcaPaused(u64),
+
+    #[error("Price {1} of schedule {0} is below its minimum price {2}")]
+    DcaPriceLimit(u64, Decimal, Decimal),
+
+    #[error("Schedule {0} belongs to another account")]
+    NotDcaOwner(u64),
+
+    #[error("Slice amount of a time-sliced order must be non-zero and at most its total amount, and its interval at least one second")]
+    InvalidTwapOrder {},
+
+    #[error("Time-sliced order {0} is not due before {1}")]
+    TwapNotDue(u64, u64),
+
+    #[error("Time-sliced order {0} is completed")]
+    TwapCompleted(u64),
+
+    #[error("Price {1} of the next slice of order {0} is below its minimum price {2}")]
+    TwapPriceLimit(u64, Decimal, Decimal),
+
+    #[error("Time-sliced order {0} belongs to another account")]
+    NotTwapOwner(u64),
+
+    #[error("A batch requires at least one action")]
+    EmptyBatch {},
+
+    #[error("DEX {0} is not local to this network, batches can only contain local actions.")]
+    BatchOverIbc(String),
+
+    #[error("Actions that take the output of a previous action can only be executed through the adapter")]
+    ChainedBatchMessages {},
 }
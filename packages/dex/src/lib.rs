@@ -6,7 +6,7 @@ pub mod msg;
 pub mod tests;
 
 // Export interface for use in SDK modules
-pub use command::{DexCommand, Fee, FeeOnInput, Return, Spread};
+pub use command::{DexCommand, Fee, FeeOnInput, Return, Spread, TotalShare};
 pub use error::DexError;
 
 pub use abstract_adapter_utils::{coins_in_assets, cw_approve_msgs, Identify};
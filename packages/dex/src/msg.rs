@@ -4,18 +4,31 @@
 
 use abstract_core::{
     adapter,
-    objects::{AnsAsset, AssetEntry, DexAssetPairing},
+    objects::{
+        AnsAsset, AssetEntry, ContractEntry, DexAssetPairing, PoolType, UncheckedPoolAddress,
+    },
 };
 use cosmwasm_schema::QueryResponses;
-use cosmwasm_std::{CosmosMsg, Decimal, Uint128};
+use cosmwasm_std::{BlockInfo, CosmosMsg, Decimal, Timestamp, Uint128};
+use cw_asset::{Asset, AssetInfoUnchecked, AssetUnchecked};
+use cw_utils::Expiration;
+
+use crate::DexError;
 
 pub type DexName = String;
 pub type OfferAsset = AnsAsset;
 pub type AskAsset = AnsAsset;
 
+/// The callback id of actions executed over ibc, as received by the contract that requested them
 pub const IBC_DEX_ID: u32 = 11335;
+/// The callback id for swap quotes requested over ibc
+pub const IBC_DEX_QUOTE_ID: u32 = 11336;
+/// The callback id of the balance requests that return the proceeds of actions executed over ibc
+pub const IBC_DEX_RETURN_ID: u32 = 11337;
+/// The callback id of the balance requests sent before actions whose proceeds are returned
+pub const IBC_DEX_BALANCE_ID: u32 = 11338;
 
-pub type ExecuteMsg = adapter::ExecuteMsg<DexExecuteMsg>;
+pub type ExecuteMsg = adapter::ExecuteMsg<DexExecuteMsg, DexKeeperMsg>;
 pub type QueryMsg = adapter::QueryMsg<DexQueryMsg>;
 pub type InstantiateMsg = adapter::InstantiateMsg<DexInstantiateMsg>;
 
@@ -38,7 +51,235 @@ pub enum DexExecuteMsg {
     Action {
         dex: DexName,
         action: DexAction,
+        /// Transfer the output of an IBC-routed action, or its offered assets if it fails, back to this chain
+        /// once its callback is received. Only native assets are returned, and only the amounts that the action added
+        /// to the balances of the remote account, which are requested before the action is sent.
+        /// Only supported when the action is executed through the adapter.
+        #[serde(default)]
+        return_proceeds: bool,
+        /// Reject the action when it's executed after this deadline.
+        /// IBC-routed actions only support a time deadline, which is checked when the action is sent
+        /// and by the host chain when it receives the action.
+        #[serde(default)]
+        deadline: Option<Expiration>,
+    },
+    /// Request a swap simulation from the dex adapter of the host chain of a remote DEX,
+    /// registered in the ANS as `<host chain>:dex_adapter`.
+    /// The result is stored by the adapter and can be read with [`DexQueryMsg::RemoteQuote`].
+    RequestRemoteQuote {
+        dex: DexName,
+        offer_asset: OfferAsset,
+        ask_asset: AssetEntry,
     },
+    /// Execute several actions on local DEXes in order, atomically.
+    /// A zero amount offered by an action is replaced by the amount of that asset received from the previous action.
+    Batch { actions: Vec<(DexName, DexAction)> },
+    /// Set the maximum price impact of the swaps of the account, can only be called by the owner of the account.
+    /// The price impact is the spread of the simulated swap relative to its return at the spot price.
+    /// Custom swaps can't be simulated and are rejected while a maximum is set.
+    SetMaxPriceImpact {
+        /// `None` removes the limit
+        max_price_impact: Option<Decimal>,
+    },
+    /// Set the oracle-deviation protection of the swaps of the account, can only be called by the owner of the account.
+    /// Custom swaps can't be checked and are rejected while a config is set.
+    SetOracleConfig {
+        /// `None` removes the protection
+        config: Option<OracleConfig>,
+    },
+    /// Set the assets that the actions of the account may use, can only be called by the owner of the account
+    SetAssetLists {
+        /// `None` removes the lists
+        lists: Option<AssetLists>,
+    },
+    /// Set the spend limit of an asset of the account, can only be called by the owner of the account
+    SetSpendLimit {
+        asset: AssetEntry,
+        /// `None` removes the limit
+        limit: Option<SpendLimit>,
+    },
+    /// Store an order that any keeper can execute on a local dex once its trigger is met.
+    /// The id of the order is returned in the `order_id` attribute of the response.
+    PlaceOrder { dex: DexName, order: Order },
+    /// Remove an open order of the account
+    CancelOrder { order_id: u64 },
+    /// Store a schedule of recurring swaps that any keeper can execute on a local dex once per interval.
+    /// The first swap is due immediately. The id of the schedule is returned in the `dca_id` attribute of the response.
+    CreateDca { dex: DexName, schedule: DcaSchedule },
+    /// Stop the executions of a schedule of the account until it's resumed
+    PauseDca { dca_id: u64 },
+    /// Resume a paused schedule of the account
+    ResumeDca { dca_id: u64 },
+    /// Remove a schedule of the account
+    CancelDca { dca_id: u64 },
+    /// Store a swap of a large amount that any keeper executes on a local dex in slices, one per interval.
+    /// The first slice is due immediately. The id of the order is returned in the `twap_id` attribute of the response.
+    PlaceTwap { dex: DexName, twap: TwapOrder },
+    /// Remove a time-sliced order of the account, the amount that wasn't swapped stays on the account
+    CancelTwap { twap_id: u64 },
+}
+
+/// Payload of the [`HostAction::App`](abstract_core::ibc_host::HostAction::App) packet of an IBC-routed action
+#[cosmwasm_schema::cw_serde]
+pub struct HostDexAction {
+    pub action: DexAction,
+    /// Checked by the host chain when it receives the packet, retried packets included
+    pub deadline: Option<Expiration>,
+}
+
+impl HostDexAction {
+    /// Action that the host executes, rejected when its deadline has passed on the host
+    pub fn into_action(self, block: &BlockInfo) -> Result<DexAction, DexError> {
+        match self.deadline {
+            Some(deadline) if deadline.is_expired(block) => {
+                Err(DexError::DeadlineExpired(deadline))
+            }
+            _ => Ok(self.action),
+        }
+    }
+}
+
+/// Permissionless messages that execute the stored orders of the accounts
+#[cosmwasm_schema::cw_serde]
+pub enum DexKeeperMsg {
+    /// Execute an order whose trigger is met, the keeper receives the keeper tip of the order
+    ExecuteOrder { order_id: u64 },
+    /// Execute the swap of a schedule that is due
+    ExecuteDca { dca_id: u64 },
+    /// Execute the next slice of a time-sliced order that is due
+    ExecuteTwapSlice { twap_id: u64 },
+}
+
+/// Recurring swap of a fixed amount
+#[cosmwasm_schema::cw_serde]
+pub struct DcaSchedule {
+    /// Asset and amount offered each interval
+    pub offer_asset: OfferAsset,
+    pub ask_asset: AssetEntry,
+    /// Minimum time between two swaps in seconds
+    pub interval: u64,
+    /// Minimum price of each swap, in ask asset per offer asset
+    pub min_price: Decimal,
+    /// Maximum spread of each swap, keepers execute them at any price within it
+    pub max_spread: Decimal,
+}
+
+/// Swap of a total amount that is split in slices executed one per interval
+#[cosmwasm_schema::cw_serde]
+pub struct TwapOrder {
+    /// Asset and total amount offered
+    pub offer_asset: OfferAsset,
+    pub ask_asset: AssetEntry,
+    /// Amount offered by each slice, the last slice offers what remains
+    pub slice_amount: Uint128,
+    /// Minimum time between two slices in seconds
+    pub interval: u64,
+    /// Minimum price of each slice, in ask asset per offer asset
+    pub min_price: Decimal,
+    /// Maximum spread of each slice, keepers execute them at any price within it
+    pub max_spread: Decimal,
+}
+
+/// Swap that is executed once the price of the offer asset reaches the trigger
+#[cosmwasm_schema::cw_serde]
+pub struct Order {
+    pub offer_asset: OfferAsset,
+    pub ask_asset: AssetEntry,
+    pub trigger: OrderTrigger,
+    /// Maximum spread of the swap relative to the trigger price, keepers execute it at any price within it
+    pub max_spread: Decimal,
+    /// Amount of the offer asset that is paid to the keeper that executes the order, taken from the offer asset
+    pub keeper_tip: Uint128,
+}
+
+/// Price of the offer asset in the ask asset at which an [`Order`] is executed.
+/// The price is the amount returned by a simulation of the swap per offered amount.
+#[cosmwasm_schema::cw_serde]
+pub enum OrderTrigger {
+    /// Executed when the price drops to or below this price
+    StopLoss(Decimal),
+    /// Executed when the price rises to or above this price
+    TakeProfit(Decimal),
+}
+
+impl OrderTrigger {
+    /// Price of the trigger, in ask asset per offer asset
+    pub fn price(&self) -> Decimal {
+        match self {
+            OrderTrigger::StopLoss(price) | OrderTrigger::TakeProfit(price) => *price,
+        }
+    }
+
+    pub fn is_met(&self, price: Decimal) -> bool {
+        match self {
+            OrderTrigger::StopLoss(trigger) => price <= *trigger,
+            OrderTrigger::TakeProfit(trigger) => price >= *trigger,
+        }
+    }
+}
+
+/// Maximum amount of an asset that the swaps and liquidity provisions of an account may offer per window
+#[cosmwasm_schema::cw_serde]
+pub struct SpendLimit {
+    pub amount: Uint128,
+    /// Length of the window in seconds, windows follow each other from the time the limit is first set
+    pub window: u64,
+}
+
+/// Assets that the actions of an account may offer, ask or provide liquidity for
+#[cosmwasm_schema::cw_serde]
+pub struct AssetLists {
+    /// Only these assets are allowed if set
+    pub allowed: Option<Vec<AssetEntry>>,
+    /// These assets are never allowed
+    pub denied: Vec<AssetEntry>,
+}
+
+impl AssetLists {
+    pub fn allows(&self, asset: &AssetEntry) -> bool {
+        !self.denied.contains(asset)
+            && self
+                .allowed
+                .as_ref()
+                .map_or(true, |allowed| allowed.contains(asset))
+    }
+}
+
+/// Reject swaps whose price deviates too far from the price given by the sources of the swapped assets
+#[cosmwasm_schema::cw_serde]
+pub struct OracleConfig {
+    /// Price source per asset, the prices of all sources must be quoted in the same asset.
+    /// Swaps of assets without a source are not checked.
+    pub sources: Vec<(AssetEntry, PriceSource)>,
+    /// Maximum deviation of the price of a swap from the price of the sources, relative to the latter
+    pub max_deviation: Decimal,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub enum PriceSource {
+    /// Oracle contract registered in the ANS, queried with [`OracleQueryMsg::Price`]
+    Oracle { contract: ContractEntry },
+    /// Time-weighted average of the spot prices observed by the adapter when swapping on `dex`.
+    /// Prices are only observed while an account uses the pair as a source.
+    Twap {
+        dex: DexName,
+        quote: AssetEntry,
+        /// Averaging window in seconds, at most a week
+        window: u64,
+    },
+}
+
+/// Query that oracle contracts used as [`PriceSource::Oracle`] have to support
+#[cosmwasm_schema::cw_serde]
+#[derive(QueryResponses)]
+pub enum OracleQueryMsg {
+    #[returns(OraclePriceResponse)]
+    Price { asset: AssetEntry },
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct OraclePriceResponse {
+    pub price: Decimal,
 }
 
 /// Possible actions to perform on the DEX
@@ -50,6 +291,9 @@ pub enum DexAction {
         /// Assets to add
         assets: Vec<OfferAsset>,
         max_spread: Option<Decimal>,
+        /// Receiver of the LP tokens, defaults to the account
+        #[serde(default)]
+        recipient: Option<Recipient>,
     },
     /// Provide liquidity equally between assets to a pool
     ProvideLiquiditySymmetric {
@@ -57,11 +301,29 @@ pub enum DexAction {
         // support complex pool types
         /// Assets that are paired with the offered asset
         paired_assets: Vec<AssetEntry>,
+        /// Maximum amount of each paired asset to send along when the action is executed over IBC.
+        /// Required for IBC-routed actions as the remote pool determines the paired amounts.
+        #[serde(default)]
+        max_paired_assets: Option<Vec<AnsAsset>>,
     },
     /// Withdraw liquidity from a pool
     WithdrawLiquidity {
         lp_token: AssetEntry,
         amount: Uint128,
+        /// Receiver of the withdrawn assets, defaults to the account
+        #[serde(default)]
+        recipient: Option<Recipient>,
+    },
+    /// Withdraw liquidity from a pool into a single one of its assets.
+    /// Only supported by dexes that can withdraw imbalanced liquidity, like stable pools.
+    WithdrawLiquiditySingleAsset {
+        lp_token: AssetEntry,
+        amount: Uint128,
+        /// Asset of the pool to withdraw the liquidity into
+        ask_asset: AssetEntry,
+        /// Receiver of the withdrawn asset, defaults to the account
+        #[serde(default)]
+        recipient: Option<Recipient>,
     },
     /// Standard swap between one asset to another
     Swap {
@@ -69,6 +331,12 @@ pub enum DexAction {
         ask_asset: AssetEntry,
         max_spread: Option<Decimal>,
         belief_price: Option<Decimal>,
+        /// Receiver of the returned asset, defaults to the account
+        #[serde(default)]
+        recipient: Option<Recipient>,
+        /// Asset that the adapter fee is charged in
+        #[serde(default)]
+        fee_mode: FeeMode,
     },
     /// Allow alternative swap routers and methods
     CustomSwap {
@@ -78,6 +346,54 @@ pub enum DexAction {
         /// Optionally supply a router to use
         router: Option<SwapRouter>,
     },
+    /// Swap on a pool that is not registered in the ANS
+    RawSwap {
+        pool: UncheckedPoolAddress,
+        offer_asset: AssetUnchecked,
+        ask_asset: AssetInfoUnchecked,
+        max_spread: Option<Decimal>,
+        belief_price: Option<Decimal>,
+    },
+    /// Provide liquidity to a pool that is not registered in the ANS
+    RawProvideLiquidity {
+        pool: UncheckedPoolAddress,
+        assets: Vec<AssetUnchecked>,
+        max_spread: Option<Decimal>,
+    },
+    /// Withdraw liquidity from a pool that is not registered in the ANS
+    RawWithdrawLiquidity {
+        pool: UncheckedPoolAddress,
+        lp_token: AssetUnchecked,
+    },
+    /// Create a pool through the factory of the dex, registered in the ANS as `<dex>:factory`.
+    /// The address of the new pool is returned in the `pool` attribute of the response.
+    CreatePool {
+        assets: Vec<AssetEntry>,
+        pool_type: PoolType,
+        /// Amounts of the assets to deposit in the new pool
+        initial_liquidity: Option<Vec<OfferAsset>>,
+    },
+}
+
+/// Receiver of the output of a [`DexAction`]
+#[cosmwasm_schema::cw_serde]
+pub enum Recipient {
+    /// Any address
+    Address(String),
+    /// Proxy of the Abstract account with this id, not supported for actions executed over IBC
+    Account(u32),
+}
+
+/// Asset that the adapter fee of a swap is charged in
+#[cosmwasm_schema::cw_serde]
+#[derive(Default)]
+pub enum FeeMode {
+    /// The fee is deducted from the offer asset before the swap
+    #[default]
+    OfferAsset,
+    /// The full offer asset is swapped and the fee is deducted from the returned ask asset.
+    /// Only supported for swaps executed on their own through the adapter.
+    AskAsset,
 }
 
 #[cosmwasm_schema::cw_serde]
@@ -98,10 +414,142 @@ pub enum DexQueryMsg {
         offer_asset: OfferAsset,
         ask_asset: AssetEntry,
         dex: Option<DexName>,
+        /// Asset that the adapter fee is charged in
+        #[serde(default)]
+        fee_mode: FeeMode,
     },
     /// Endpoint can be used by front-end to easily interact with contracts.
     #[returns(GenerateMessagesResponse)]
-    GenerateMessages { message: DexExecuteMsg },
+    GenerateMessages {
+        message: DexExecuteMsg,
+        /// Proxy address of the account that executes the messages.
+        /// Required for IBC-routed actions and fee updates.
+        proxy_address: Option<String>,
+    },
+    /// Cumulative swap statistics of an account, per DEX and offer asset
+    #[returns(AccountStatsResponse)]
+    AccountStats {
+        /// Proxy address of the account
+        account: String,
+        start_after: Option<(DexName, AssetEntry)>,
+        limit: Option<u8>,
+    },
+    /// Asset pairs ordered by the number of swaps executed through the adapter
+    #[returns(TopPairsResponse)]
+    TopPairs {
+        /// Swap count and pairing of the last pair of the previous page, as returned in that page
+        start_after: Option<(u64, DexAssetPairing)>,
+        limit: Option<u8>,
+    },
+    /// Latest swap quote received from a remote DEX, see [`DexExecuteMsg::RequestRemoteQuote`]
+    #[returns(RemoteQuoteResponse)]
+    RemoteQuote {
+        dex: DexName,
+        offer_asset: AssetEntry,
+        ask_asset: AssetEntry,
+    },
+    /// Actions of an account that were sent to a remote chain over IBC
+    #[returns(IbcActionsResponse)]
+    IbcActions {
+        /// Proxy address of the account
+        account: String,
+        start_after: Option<u64>,
+        limit: Option<u8>,
+    },
+    /// Maximum price impact of the swaps of an account
+    #[returns(MaxPriceImpactResponse)]
+    MaxPriceImpact {
+        /// Proxy address of the account
+        account: String,
+    },
+    /// Oracle-deviation protection of the swaps of an account
+    #[returns(OracleConfigResponse)]
+    OracleConfig {
+        /// Proxy address of the account
+        account: String,
+    },
+    /// Asset lists of an account
+    #[returns(AssetListsResponse)]
+    AssetLists {
+        /// Proxy address of the account
+        account: String,
+    },
+    /// Liquidity positions of an account on the dexes available on this chain,
+    /// found by scanning the LP tokens registered in the ANS
+    #[returns(PositionsResponse)]
+    Positions {
+        /// Proxy address of the account
+        account: String,
+        /// `next_start_after` of the previous page
+        start_after: Option<AssetEntry>,
+        limit: Option<u8>,
+    },
+    /// Liquidity provided by an account through the adapter, with its entry price and impermanent loss
+    #[returns(LpEntriesResponse)]
+    LpEntries {
+        /// Proxy address of the account
+        account: String,
+        start_after: Option<(DexName, AssetEntry)>,
+        limit: Option<u8>,
+    },
+    /// Open orders of an account
+    #[returns(OrdersResponse)]
+    Orders {
+        /// Proxy address of the account
+        account: String,
+        start_after: Option<u64>,
+        limit: Option<u8>,
+    },
+    /// Dollar-cost-averaging schedules of an account
+    #[returns(DcaSchedulesResponse)]
+    DcaSchedules {
+        /// Proxy address of the account
+        account: String,
+        start_after: Option<u64>,
+        limit: Option<u8>,
+    },
+    /// Executed swaps of a schedule, oldest first
+    #[returns(DcaHistoryResponse)]
+    DcaHistory {
+        dca_id: u64,
+        /// Execution time in seconds
+        start_after: Option<u64>,
+        limit: Option<u8>,
+    },
+    /// Time-sliced orders of an account and their progress
+    #[returns(TwapOrdersResponse)]
+    TwapOrders {
+        /// Proxy address of the account
+        account: String,
+        start_after: Option<u64>,
+        limit: Option<u8>,
+    },
+    /// Underlying assets of an amount of LP tokens of a local dex
+    #[returns(LpValueResponse)]
+    LpValue {
+        dex: DexName,
+        lp_token: AssetEntry,
+        amount: Uint128,
+        /// Also value the underlying assets in this asset at the spot prices of their pools on the dex
+        quote_asset: Option<AssetEntry>,
+    },
+    /// Expected balance changes of an account from executing an action on a local dex.
+    /// Fails if the action would be rejected by the settings of the account.
+    #[returns(DryRunResponse)]
+    DryRun {
+        dex: DexName,
+        action: DexAction,
+        /// Proxy address of the account
+        account: String,
+    },
+    /// Spend limits of an account and their remaining allowance
+    #[returns(SpendLimitsResponse)]
+    SpendLimits {
+        /// Proxy address of the account
+        account: String,
+        start_after: Option<AssetEntry>,
+        limit: Option<u8>,
+    },
 }
 
 // LP/protocol fees could be withheld from either input or output so commission asset must be included.
@@ -114,8 +562,263 @@ pub struct SimulateSwapResponse {
     pub spread_amount: Uint128,
     /// Commission charged for the swap
     pub commission: (AssetEntry, Uint128),
-    /// Adapter fee charged for the swap (paid in offer asset)
+    /// Adapter fee charged for the swap, paid in the asset of `fee_mode`
     pub usage_fee: Uint128,
+    /// Asset that the adapter fee is paid in
+    #[serde(default)]
+    pub fee_mode: FeeMode,
+}
+
+/// Swap quote received from a remote DEX
+#[cosmwasm_schema::cw_serde]
+pub struct RemoteQuote {
+    /// The simulated offer asset
+    pub offer_asset: OfferAsset,
+    pub quote: SimulateSwapResponse,
+    /// Time at which the quote was received
+    pub updated_at: Timestamp,
+}
+
+/// Response from RemoteQuote
+#[cosmwasm_schema::cw_serde]
+pub struct RemoteQuoteResponse {
+    pub offer_asset: OfferAsset,
+    pub quote: SimulateSwapResponse,
+    pub updated_at: Timestamp,
+    /// Age of the quote in seconds
+    pub age: u64,
+}
+
+/// Cumulative statistics of swaps executed through the adapter
+#[cosmwasm_schema::cw_serde]
+#[derive(Default)]
+pub struct TradeStats {
+    /// Swapped volume, denominated in the offer asset
+    pub volume: Uint128,
+    /// Adapter fees paid, denominated in the offer asset
+    pub fees: Uint128,
+    /// Number of swaps
+    pub swaps: u64,
+}
+
+/// Liquidity provided by an account to a two-asset pool through the adapter
+#[cosmwasm_schema::cw_serde]
+pub struct LpEntry {
+    /// Deposited amounts of the pool assets, reduced in proportion to the LP tokens withdrawn
+    pub deposits: Vec<AnsAsset>,
+    /// Spot price of the first pool asset in the second in the pool when the deposits were made,
+    /// averaged over the deposited amounts of the first asset
+    pub entry_price: Decimal,
+    /// LP tokens minted for the deposits, estimated from the pool reserves at deposit time and reduced like the deposits.
+    /// Zero if the dex doesn't expose the reserves of its pools.
+    #[serde(default)]
+    pub lp_amount: Uint128,
+}
+
+/// Response from AccountStats
+#[cosmwasm_schema::cw_serde]
+pub struct AccountStatsResponse {
+    /// statistics per (dex, offer asset)
+    pub stats: Vec<(DexName, AssetEntry, TradeStats)>,
+}
+
+/// Response from TopPairs
+#[cosmwasm_schema::cw_serde]
+pub struct TopPairsResponse {
+    /// statistics per (offer asset, ask asset, dex), most traded first
+    pub pairs: Vec<(DexAssetPairing, TradeStats)>,
+}
+
+/// Status of an action that was sent to a remote chain over IBC
+#[cosmwasm_schema::cw_serde]
+pub enum IbcActionStatus {
+    /// Waiting for the IBC callback
+    Pending,
+    /// The action was executed on the remote chain
+    Succeeded,
+    /// The action failed on the remote chain
+    Failed { error: String },
+}
+
+/// Action that was sent to a remote chain over IBC
+#[cosmwasm_schema::cw_serde]
+pub struct IbcActionInfo {
+    pub dex: DexName,
+    pub action: DexAction,
+    pub status: IbcActionStatus,
+}
+
+/// Response from IbcActions
+#[cosmwasm_schema::cw_serde]
+pub struct IbcActionsResponse {
+    /// actions per id, in the order they were sent
+    pub actions: Vec<(u64, IbcActionInfo)>,
+}
+
+/// Response from MaxPriceImpact
+#[cosmwasm_schema::cw_serde]
+pub struct MaxPriceImpactResponse {
+    pub max_price_impact: Option<Decimal>,
+}
+
+/// Response from OracleConfig
+#[cosmwasm_schema::cw_serde]
+pub struct OracleConfigResponse {
+    pub config: Option<OracleConfig>,
+}
+
+/// Response from AssetLists
+#[cosmwasm_schema::cw_serde]
+pub struct AssetListsResponse {
+    pub lists: Option<AssetLists>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct SpendAllowance {
+    pub limit: SpendLimit,
+    /// Amount spent in the current window
+    pub spent: Uint128,
+    pub remaining: Uint128,
+    /// Time in seconds at which the current window ends, `None` if no window is running
+    pub resets_at: Option<u64>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct Position {
+    pub dex: DexName,
+    pub lp_token: AssetEntry,
+    /// LP tokens held by the account
+    pub balance: Uint128,
+    /// Amounts of the pool assets that the LP tokens are redeemable for,
+    /// empty if the dex doesn't expose the reserves of its pools
+    pub assets: Vec<AnsAsset>,
+    /// Share of the pool held by the account, `None` if the dex doesn't expose the reserves of its pools
+    pub share: Option<Decimal>,
+}
+
+/// Response from Positions
+#[cosmwasm_schema::cw_serde]
+pub struct PositionsResponse {
+    pub positions: Vec<Position>,
+    /// Last asset of the ANS that was scanned for positions, `None` once all assets are scanned.
+    /// A page holds less than `limit` positions when the scan stops after a bounded number of assets.
+    pub next_start_after: Option<AssetEntry>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct LpEntryInfo {
+    pub dex: DexName,
+    pub lp_token: AssetEntry,
+    pub entry: LpEntry,
+    /// Value of the LP tokens of the entry that the account still holds, in the second pool asset at the current pool price.
+    /// `None` if the dex doesn't expose the reserves of its pools.
+    pub current_value: Option<Uint128>,
+    /// Value of the deposits if they had been held instead, in the second pool asset at the current pool price
+    pub hodl_value: Option<Uint128>,
+    /// Loss of the current value relative to the HODL value, zero if the position is worth at least as much
+    pub impermanent_loss: Option<Decimal>,
+}
+
+/// Response from LpEntries
+#[cosmwasm_schema::cw_serde]
+pub struct LpEntriesResponse {
+    pub entries: Vec<LpEntryInfo>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct OrderInfo {
+    pub order_id: u64,
+    pub dex: DexName,
+    pub order: Order,
+}
+
+/// Response from Orders
+#[cosmwasm_schema::cw_serde]
+pub struct OrdersResponse {
+    pub orders: Vec<OrderInfo>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct DcaInfo {
+    pub dca_id: u64,
+    pub dex: DexName,
+    pub schedule: DcaSchedule,
+    /// Time in seconds from which the next swap can be executed
+    pub next_execution: u64,
+    pub paused: bool,
+    /// Number of executed swaps
+    pub executions: u64,
+}
+
+/// Response from DcaSchedules
+#[cosmwasm_schema::cw_serde]
+pub struct DcaSchedulesResponse {
+    pub schedules: Vec<DcaInfo>,
+}
+
+/// Swap executed for a schedule
+#[cosmwasm_schema::cw_serde]
+pub struct DcaExecution {
+    /// Execution time in seconds
+    pub time: u64,
+    pub offered: Uint128,
+    /// Return of the swap simulated before its execution
+    pub expected_return: Uint128,
+}
+
+/// Response from DcaHistory
+#[cosmwasm_schema::cw_serde]
+pub struct DcaHistoryResponse {
+    pub executions: Vec<DcaExecution>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct TwapInfo {
+    pub twap_id: u64,
+    pub dex: DexName,
+    pub twap: TwapOrder,
+    /// Amount of the offer asset swapped so far
+    pub offered: Uint128,
+    /// Sum of the returns of the executed slices, simulated before their execution
+    pub expected_return: Uint128,
+    /// Number of executed slices
+    pub slices: u64,
+    /// Time in seconds from which the next slice can be executed
+    pub next_execution: u64,
+    /// Whether the total amount has been swapped
+    pub completed: bool,
+}
+
+/// Response from TwapOrders
+#[cosmwasm_schema::cw_serde]
+pub struct TwapOrdersResponse {
+    pub orders: Vec<TwapInfo>,
+}
+
+/// Response from LpValue
+#[cosmwasm_schema::cw_serde]
+pub struct LpValueResponse {
+    /// Amounts of the pool assets that the LP tokens are redeemable for
+    pub assets: Vec<AnsAsset>,
+    /// Total value of the assets in the quote asset, if requested
+    pub value: Option<AnsAsset>,
+}
+
+/// Response from DryRun
+#[cosmwasm_schema::cw_serde]
+pub struct DryRunResponse {
+    /// Assets that leave the account, including fees
+    pub offered: Vec<Asset>,
+    /// Assets that the account receives, empty when they're sent to another recipient
+    pub received: Vec<Asset>,
+    /// Usage fee of the adapter and commission of the dex
+    pub fees: Vec<Asset>,
+}
+
+/// Response from SpendLimits
+#[cosmwasm_schema::cw_serde]
+pub struct SpendLimitsResponse {
+    pub limits: Vec<(AssetEntry, SpendAllowance)>,
 }
 
 /// Response from GenerateMsgs